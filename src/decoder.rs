@@ -1,56 +1,94 @@
 use crate::error::{QrScoreError, Result};
-use crate::types::{DecodeResult, ErrorCorrectionLevel, QrMetadata};
-use image::{DynamicImage, GrayImage};
+use crate::geometry::{locate_grid, GridLocation};
+use crate::types::{DecodeResult, DecoderKind, ErrorCorrectionLevel, QrMetadata, SymbolFormat, TestConfig};
+use image::metadata::Orientation;
+use image::{DynamicImage, GrayImage, ImageDecoder};
 use rxing::common::{GlobalHistogramBinarizer, HybridBinarizer};
 use rxing::{
     BarcodeFormat, Binarizer, BinaryBitmap, DecodeHints, Luma8LuminanceSource,
     MultiFormatReader, Reader, RXingResultMetadataType, RXingResultMetadataValue,
 };
-use std::collections::HashSet;
 
 #[derive(Debug, Clone)]
 struct RawDecode {
     content: String,
+    format: SymbolFormat,
     error_correction: Option<ErrorCorrectionLevel>,
+    corners: Option<[(f32, f32); 4]>,
+    version: Option<u8>,
 }
 
 impl RawDecode {
+    /// QR metadata only describes QR codes, so other symbologies get none.
     fn into_result(self) -> DecodeResult {
+        let metadata = (self.format == SymbolFormat::Qr).then(|| QrMetadata {
+            error_correction: self.error_correction.unwrap_or(ErrorCorrectionLevel::M),
+            version: self.version,
+        });
         DecodeResult {
             content: self.content,
-            metadata: Some(QrMetadata {
-                error_correction: self.error_correction.unwrap_or(ErrorCorrectionLevel::M),
-            }),
+            format: self.format,
+            metadata,
+            corners: self.corners,
+            frame: 0,
         }
     }
 }
 
-fn rxing_hints() -> DecodeHints {
+fn to_barcode_format(format: SymbolFormat) -> BarcodeFormat {
+    match format {
+        SymbolFormat::Qr => BarcodeFormat::QR_CODE,
+        SymbolFormat::DataMatrix => BarcodeFormat::DATA_MATRIX,
+        SymbolFormat::Aztec => BarcodeFormat::AZTEC,
+        SymbolFormat::Pdf417 => BarcodeFormat::PDF_417,
+    }
+}
+
+fn from_barcode_format(format: &BarcodeFormat) -> Result<SymbolFormat> {
+    match format {
+        BarcodeFormat::QR_CODE => Ok(SymbolFormat::Qr),
+        BarcodeFormat::DATA_MATRIX => Ok(SymbolFormat::DataMatrix),
+        BarcodeFormat::AZTEC => Ok(SymbolFormat::Aztec),
+        BarcodeFormat::PDF_417 => Ok(SymbolFormat::Pdf417),
+        other => Err(QrScoreError::UnsupportedFormat(format!("{:?}", other))),
+    }
+}
+
+fn rxing_hints(formats: &[SymbolFormat]) -> DecodeHints {
     DecodeHints {
         AlsoInverted: Some(true),
         TryHarder: Some(true),
-        PossibleFormats: Some(HashSet::from([BarcodeFormat::QR_CODE])),
+        PossibleFormats: Some(formats.iter().copied().map(to_barcode_format).collect()),
         ..DecodeHints::default()
     }
 }
 
-fn decode_rxing_with<B, F>(luma_data: &[u8], width: u32, height: u32, make_binarizer: F) -> Result<RawDecode>
+fn decode_rxing_with<B, F>(
+    luma_data: &[u8],
+    width: u32,
+    height: u32,
+    formats: &[SymbolFormat],
+    make_binarizer: F,
+) -> Result<RawDecode>
 where
     B: Binarizer + 'static,
     F: FnOnce(Luma8LuminanceSource) -> B + Send + 'static,
 {
     let luma = luma_data.to_vec();
+    let hints = rxing_hints(formats);
 
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || {
         let source = Luma8LuminanceSource::new(luma, width, height);
         let binarizer = make_binarizer(source);
         let mut bitmap = BinaryBitmap::new(binarizer);
         let mut reader = MultiFormatReader::default();
-        reader.decode_with_hints(&mut bitmap, &rxing_hints())
+        reader.decode_with_hints(&mut bitmap, &hints)
     }));
 
+    // rxing reports NOT_FOUND for any failure, so it can't tell a missing
+    // symbol from an unreadable one.
     let r = result
-        .map_err(|_| QrScoreError::DecodeFailed)?
+        .map_err(|_| QrScoreError::DecoderPanicked)?
         .map_err(|_| QrScoreError::DecodeFailed)?;
 
     let ec = r
@@ -63,10 +101,26 @@ where
 
     Ok(RawDecode {
         content: r.getText().to_string(),
+        format: from_barcode_format(r.getBarcodeFormat())?,
         error_correction: ec,
+        corners: rxing_corners(r.getRXingResultPoints()),
+        version: None,
     })
 }
 
+/// rxing's primary QR reader reports the symbol corners as top-left, top-right,
+/// bottom-right, bottom-left. Its legacy fallback reports only the finder
+/// pattern centers as bottom-left, top-left, top-right, so complete the
+/// parallelogram to get a fourth point and reorder.
+fn rxing_corners(points: &[rxing::Point]) -> Option<[(f32, f32); 4]> {
+    let p: Vec<(f32, f32)> = points.iter().map(|p| (p.x, p.y)).collect();
+    match p[..] {
+        [tl, tr, br, bl] => Some([tl, tr, br, bl]),
+        [bl, tl, tr] => Some([tl, tr, (tr.0 + bl.0 - tl.0, tr.1 + bl.1 - tl.1), bl]),
+        _ => None,
+    }
+}
+
 fn decode_rqrr(luma_data: &[u8], width: u32, height: u32) -> Result<RawDecode> {
     let luma = GrayImage::from_raw(width, height, luma_data.to_vec())
         .ok_or(QrScoreError::DecodeFailed)?;
@@ -74,45 +128,194 @@ fn decode_rqrr(luma_data: &[u8], width: u32, height: u32) -> Result<RawDecode> {
     let mut prepared = rqrr::PreparedImage::prepare(luma);
     let grids = prepared.detect_grids();
     let grid = grids.first().ok_or(QrScoreError::DecodeFailed)?;
-    let (meta, content) = grid.decode().map_err(|_| QrScoreError::DecodeFailed)?;
+    let (meta, content) = grid.decode().map_err(|_| QrScoreError::UnreadableSymbol)?;
 
     Ok(RawDecode {
         content,
+        format: SymbolFormat::Qr,
         error_correction: Some(convert_rqrr_ec(meta.ecc_level)),
+        corners: Some(crate::geometry::grid_corners(grid)),
+        version: u8::try_from(meta.version.0).ok(),
     })
 }
 
-/// Try all decoders: rxing hybrid, rxing global histogram, rqrr normal, rqrr inverted.
-pub fn try_decode(img: &DynamicImage) -> Result<DecodeResult> {
-    let luma = img.to_luma8();
+/// Try each decoder in `config.decoders` in turn, by default rxing hybrid,
+/// rxing global histogram, rqrr normal, rqrr inverted.
+///
+/// rxing looks for every format in `config.formats` and handles light-on-dark
+/// codes itself; rqrr is QR-only, so it is skipped unless QR is among them.
+pub fn try_decode(img: &DynamicImage, config: &TestConfig) -> Result<DecodeResult> {
+    match img.as_luma8() {
+        Some(luma) => try_decode_luma(luma, config),
+        None => try_decode_luma(&img.to_luma8(), config),
+    }
+}
+
+/// [`try_decode`] on an 8-bit luma buffer, skipping the color conversion.
+pub fn try_decode_luma(luma: &GrayImage, config: &TestConfig) -> Result<DecodeResult> {
     let (width, height) = luma.dimensions();
-    let luma_data = luma.into_raw();
+    let luma_data = luma.as_raw();
+    let formats = &config.formats;
+    let qr = formats.contains(&SymbolFormat::Qr);
+    let mut failure = QrScoreError::DecodeFailed;
 
-    if let Ok(r) = decode_rxing_with(&luma_data, width, height, HybridBinarizer::new) {
-        return Ok(r.into_result());
+    for decoder in &config.decoders {
+        let attempt = match decoder {
+            DecoderKind::RxingHybrid => decode_rxing_with(luma_data, width, height, formats, HybridBinarizer::new),
+            DecoderKind::RxingGlobalHistogram => {
+                decode_rxing_with(luma_data, width, height, formats, GlobalHistogramBinarizer::new)
+            }
+            DecoderKind::Rqrr if qr => decode_rqrr(luma_data, width, height),
+            DecoderKind::RqrrInverted if qr => {
+                let inverted: Vec<u8> = luma_data.iter().map(|&v| 255 - v).collect();
+                decode_rqrr(&inverted, width, height)
+            }
+            DecoderKind::Rqrr | DecoderKind::RqrrInverted => continue,
+        };
+        match attempt {
+            Ok(r) => return Ok(r.into_result()),
+            Err(e) => failure = most_specific(failure, e),
+        }
     }
 
-    if let Ok(r) = decode_rxing_with(&luma_data, width, height, GlobalHistogramBinarizer::new) {
-        return Ok(r.into_result());
+    Err(failure)
+}
+
+/// Keep whichever decode failure says the most about the symbol: one that
+/// was found but unreadable beats a decoder panic, which beats not found.
+fn most_specific(a: QrScoreError, b: QrScoreError) -> QrScoreError {
+    let rank = |e: &QrScoreError| match e {
+        QrScoreError::UnreadableSymbol => 2,
+        QrScoreError::DecoderPanicked => 1,
+        _ => 0,
+    };
+    if rank(&b) > rank(&a) { b } else { a }
+}
+
+/// Load raw image bytes, rotating/flipping per the EXIF orientation tag when
+/// `respect_exif` is set. Formats without EXIF (including rendered SVGs) load as-is.
+pub fn load_image(image_bytes: &[u8], respect_exif: bool) -> Result<DynamicImage> {
+    let load_err = |e: image::ImageError| QrScoreError::ImageLoad(e.to_string());
+    let mut decoder = image::ImageReader::new(std::io::Cursor::new(image_bytes))
+        .with_guessed_format()
+        .map_err(|e| QrScoreError::ImageLoad(e.to_string()))?
+        .into_decoder()
+        .map_err(load_err)?;
+
+    let orientation = match respect_exif {
+        true => decoder.orientation().unwrap_or(Orientation::NoTransforms),
+        false => Orientation::NoTransforms,
+    };
+    let mut img = DynamicImage::from_decoder(decoder).map_err(load_err)?;
+    img.apply_orientation(orientation);
+    Ok(img)
+}
+
+/// rqrr's grid for a symbol decoded as `format`, or `None` for symbologies
+/// other than QR, which it can't locate. Detection is too slow to repeat, so
+/// entry points call this once and share the result.
+pub(crate) fn locate_symbol(img: &DynamicImage, format: SymbolFormat) -> Option<GridLocation> {
+    match format {
+        SymbolFormat::Qr => locate_grid(img),
+        _ => None,
     }
+}
 
-    if let Ok(r) = decode_rqrr(&luma_data, width, height) {
-        return Ok(r.into_result());
+/// rxing doesn't report the QR version, so measure it from the located grid.
+pub(crate) fn fill_version(result: &mut DecodeResult, location: Option<&GridLocation>) {
+    let Some(meta) = result.metadata.as_mut() else { return };
+    if meta.version.is_some() || result.format != SymbolFormat::Qr {
+        return;
     }
+    meta.version = location
+        .and_then(|loc| loc.modules.checked_sub(17))
+        .and_then(|n| u8::try_from(n / 4).ok());
+}
+
+/// Every frame of an animated GIF, APNG or WebP, composited to full-canvas
+/// RGBA. Anything else, including a single-frame animation, loads exactly
+/// as [`load_image`] does.
+///
+/// The canvas is checked against `config.max_dimension` from the header,
+/// before any frame is decoded, and reading stops after `config.max_frames`.
+pub fn load_frames(image_bytes: &[u8], config: &TestConfig) -> Result<Vec<DynamicImage>> {
+    use image::codecs::{gif::GifDecoder, png::PngDecoder, webp::WebPDecoder};
+    use image::{AnimationDecoder, Frames, ImageDecoder, ImageFormat};
 
-    let inverted: Vec<u8> = luma_data.iter().map(|&v| 255 - v).collect();
-    if let Ok(r) = decode_rqrr(&inverted, width, height) {
-        return Ok(r.into_result());
+    let load_err = |e: image::ImageError| QrScoreError::ImageLoad(e.to_string());
+    let check = |decoder: &dyn ImageDecoder| {
+        let (width, height) = decoder.dimensions();
+        crate::validate_dimensions(width, height, config.max_dimension)
+    };
+    let collect = |frames: Frames| -> Result<Vec<DynamicImage>> {
+        frames
+            .take(config.max_frames.unwrap_or(crate::MAX_FRAMES))
+            .map(|f| f.map(|f| DynamicImage::ImageRgba8(f.into_buffer())).map_err(load_err))
+            .collect()
+    };
+    let cursor = || std::io::Cursor::new(image_bytes);
+
+    let frames = match image::guess_format(image_bytes) {
+        Ok(ImageFormat::Gif) => {
+            let decoder = GifDecoder::new(cursor()).map_err(load_err)?;
+            check(&decoder)?;
+            collect(decoder.into_frames())?
+        }
+        Ok(ImageFormat::Png) => {
+            let decoder = PngDecoder::new(cursor()).map_err(load_err)?;
+            if decoder.is_apng().map_err(load_err)? {
+                check(&decoder)?;
+                collect(decoder.apng().map_err(load_err)?.into_frames())?
+            } else {
+                Vec::new()
+            }
+        }
+        Ok(ImageFormat::WebP) => {
+            let decoder = WebPDecoder::new(cursor()).map_err(load_err)?;
+            if decoder.has_animation() {
+                check(&decoder)?;
+                collect(decoder.into_frames())?
+            } else {
+                Vec::new()
+            }
+        }
+        _ => Vec::new(),
+    };
+
+    if frames.len() > 1 {
+        Ok(frames)
+    } else {
+        Ok(vec![load_image(image_bytes, config.respect_exif)?])
     }
+}
 
-    Err(QrScoreError::DecodeFailed)
+/// Decode every frame of an animated input (see [`load_frames`]), returning
+/// those that decoded with [`DecodeResult::frame`] set. Frames that don't
+/// decode are left out, so the result may be empty.
+pub fn decode_frames(image_bytes: &[u8]) -> Result<Vec<DecodeResult>> {
+    let config = TestConfig::default();
+    let frames = load_frames(image_bytes, &config)?;
+    Ok(frames
+        .iter()
+        .enumerate()
+        .filter_map(|(index, img)| {
+            let mut result = try_decode(img, &config).ok()?;
+            let location = locate_symbol(img, result.format);
+            fill_version(&mut result, location.as_ref());
+            result.frame = index;
+            Some(result)
+        })
+        .collect())
 }
 
 /// Decode from raw image bytes (PNG, JPEG, etc.)
 pub fn multi_decode(image_bytes: &[u8]) -> Result<DecodeResult> {
-    let img = image::load_from_memory(image_bytes)
-        .map_err(|e| QrScoreError::ImageLoad(e.to_string()))?;
-    try_decode(&img)
+    let config = TestConfig::default();
+    let img = load_image(image_bytes, config.respect_exif)?;
+    let mut result = try_decode(&img, &config)?;
+    let location = locate_symbol(&img, result.format);
+    fill_version(&mut result, location.as_ref());
+    Ok(result)
 }
 
 fn parse_ec_level(s: &str) -> Option<ErrorCorrectionLevel> {
@@ -153,6 +356,86 @@ mod tests {
         buf
     }
 
+    fn luma_qr(inverted: bool) -> GrayImage {
+        let code = qrcode::QrCode::new(b"https://example.com").unwrap();
+        let mut img = code.render::<image::Luma<u8>>().build();
+        if inverted {
+            image::imageops::invert(&mut img);
+        }
+        img
+    }
+
+    #[test]
+    fn decoders_run_only_those_listed() {
+        let rqrr_only = TestConfig { decoders: vec![DecoderKind::Rqrr], ..TestConfig::default() };
+        assert!(try_decode_luma(&luma_qr(false), &rqrr_only).is_ok());
+        assert!(try_decode_luma(&luma_qr(true), &rqrr_only).is_err());
+
+        let with_inverted = TestConfig {
+            decoders: vec![DecoderKind::Rqrr, DecoderKind::RqrrInverted],
+            ..TestConfig::default()
+        };
+        assert!(try_decode_luma(&luma_qr(true), &with_inverted).is_ok());
+
+        let none = TestConfig { decoders: Vec::new(), ..TestConfig::default() };
+        assert!(matches!(try_decode_luma(&luma_qr(false), &none), Err(QrScoreError::DecodeFailed)));
+    }
+
+    #[test]
+    fn rqrr_decoders_skipped_without_qr_format() {
+        let config = TestConfig {
+            decoders: vec![DecoderKind::Rqrr],
+            formats: vec![SymbolFormat::DataMatrix],
+            ..TestConfig::default()
+        };
+        assert!(try_decode_luma(&luma_qr(false), &config).is_err());
+    }
+
+    fn animated_gif(frames: Vec<image::RgbaImage>) -> Vec<u8> {
+        let mut buf = Vec::new();
+        {
+            let mut encoder = image::codecs::gif::GifEncoder::new(&mut buf);
+            encoder.encode_frames(frames.into_iter().map(image::Frame::new)).unwrap();
+        }
+        buf
+    }
+
+    fn qr_rgba() -> image::RgbaImage {
+        DynamicImage::ImageLuma8(luma_qr(false)).to_rgba8()
+    }
+
+    #[test]
+    fn decode_frames_finds_later_frames() {
+        let qr = qr_rgba();
+        let blank = image::RgbaImage::from_pixel(qr.width(), qr.height(), image::Rgba([255, 255, 255, 255]));
+        let gif = animated_gif(vec![blank.clone(), qr.clone(), blank, qr]);
+
+        assert_eq!(load_frames(&gif, &TestConfig::default()).unwrap().len(), 4);
+        let decoded = decode_frames(&gif).unwrap();
+        assert_eq!(decoded.iter().map(|r| r.frame).collect::<Vec<_>>(), vec![1, 3]);
+        assert!(decoded.iter().all(|r| r.content == "https://example.com"));
+    }
+
+    #[test]
+    fn load_frames_checks_limits_before_decoding() {
+        let qr = qr_rgba();
+        let gif = animated_gif(vec![qr.clone(), qr.clone(), qr.clone(), qr]);
+
+        let config = TestConfig::builder().max_frames(2).build();
+        assert_eq!(load_frames(&gif, &config).unwrap().len(), 2);
+
+        let config = TestConfig::builder().max_dimension(qr_rgba().width() - 1).build();
+        assert!(matches!(load_frames(&gif, &config), Err(QrScoreError::DimensionsTooLarge { .. })));
+    }
+
+    #[test]
+    fn decode_frames_single_image_is_one_frame() {
+        let decoded = decode_frames(&create_test_qr()).unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].frame, 0);
+        assert!(decode_frames(b"not an image").is_err());
+    }
+
     #[test]
     fn decode_simple_qr() {
         let qr_bytes = create_test_qr();
@@ -167,12 +450,67 @@ mod tests {
         assert!(result.metadata.is_some());
     }
 
+    #[test]
+    fn decode_provides_version() {
+        let result = multi_decode(&create_test_qr()).unwrap();
+        assert_eq!(result.metadata.unwrap().version, Some(2));
+    }
+
+    #[test]
+    fn rqrr_reports_version() {
+        let img = image::load_from_memory(&create_test_qr()).unwrap().to_luma8();
+        let (w, h) = img.dimensions();
+        assert_eq!(decode_rqrr(img.as_raw(), w, h).unwrap().version, Some(2));
+    }
+
     #[test]
     fn decode_invalid_image_returns_error() {
         let result = multi_decode(b"not an image at all");
         assert!(result.is_err());
     }
 
+    /// Minimal big-endian EXIF block holding only an orientation tag.
+    fn exif_orientation(value: u16) -> Vec<u8> {
+        let mut exif = b"MM\x00\x2a\x00\x00\x00\x08\x00\x01\x01\x12\x00\x03\x00\x00\x00\x01".to_vec();
+        exif.extend_from_slice(&value.to_be_bytes());
+        exif.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        exif
+    }
+
+    fn encode_png_with_exif(img: &DynamicImage, exif: Vec<u8>) -> Vec<u8> {
+        use image::ImageEncoder;
+        let mut buf = Vec::new();
+        let mut encoder = image::codecs::png::PngEncoder::new(&mut buf);
+        encoder.set_exif_metadata(exif).unwrap();
+        let luma = img.to_luma8();
+        encoder
+            .write_image(luma.as_raw(), luma.width(), luma.height(), image::ExtendedColorType::L8)
+            .unwrap();
+        buf
+    }
+
+    #[test]
+    fn load_image_applies_exif_orientation() {
+        let upright = image::load_from_memory(&create_test_qr()).unwrap();
+        let upright = upright.crop_imm(0, 0, upright.width(), upright.height() - 40);
+        // Orientation 6 means "rotate 90° clockwise to display", so store it rotated the other way
+        let stored = upright.rotate270();
+        let bytes = encode_png_with_exif(&stored, exif_orientation(6));
+
+        let loaded = load_image(&bytes, true).unwrap();
+        assert_eq!(loaded.to_luma8(), upright.to_luma8());
+
+        let raw = load_image(&bytes, false).unwrap();
+        assert_eq!(raw.to_luma8(), stored.to_luma8());
+    }
+
+    #[test]
+    fn load_image_without_exif_is_unchanged() {
+        let bytes = create_test_qr();
+        let loaded = load_image(&bytes, true).unwrap();
+        assert_eq!(loaded.to_luma8(), image::load_from_memory(&bytes).unwrap().to_luma8());
+    }
+
     #[test]
     fn parse_ec_level_all_variants() {
         assert_eq!(parse_ec_level("L"), Some(ErrorCorrectionLevel::L));
@@ -192,6 +530,100 @@ mod tests {
         assert_eq!(convert_rqrr_ec(99), ErrorCorrectionLevel::M); // unknown → M
     }
 
+    #[test]
+    fn decode_reports_corners() {
+        let qr_bytes = create_test_qr();
+        let corners = multi_decode(&qr_bytes).unwrap().corners.unwrap();
+        // 25x25 symbol at 8px per module with a 4 module quiet zone
+        let expected = [(32.0, 32.0), (232.0, 32.0), (232.0, 232.0), (32.0, 232.0)];
+        for ((x, y), (ex, ey)) in corners.iter().zip(expected) {
+            assert!((x - ex).abs() <= 1.0 && (y - ey).abs() <= 1.0, "{:?}", corners);
+        }
+    }
+
+    #[test]
+    fn rqrr_corners_are_symbol_edges() {
+        let img = image::load_from_memory(&create_test_qr()).unwrap().to_luma8();
+        let (w, h) = img.dimensions();
+        let corners = decode_rqrr(img.as_raw(), w, h).unwrap().corners.unwrap();
+        let expected = [(32.0, 32.0), (232.0, 32.0), (232.0, 232.0), (32.0, 232.0)];
+        for ((x, y), (ex, ey)) in corners.iter().zip(expected) {
+            assert!((x - ex).abs() <= 1.0 && (y - ey).abs() <= 1.0, "{:?}", corners);
+        }
+    }
+
+    #[test]
+    fn rxing_corners_from_finder_centers() {
+        let points = [
+            rxing::Point { x: 10.0, y: 90.0 },
+            rxing::Point { x: 10.0, y: 10.0 },
+            rxing::Point { x: 90.0, y: 10.0 },
+        ];
+        assert_eq!(
+            rxing_corners(&points),
+            Some([(10.0, 10.0), (90.0, 10.0), (90.0, 90.0), (10.0, 90.0)])
+        );
+        assert_eq!(rxing_corners(&points[..2]), None);
+    }
+
+    fn create_test_data_matrix() -> DynamicImage {
+        use rxing::Writer;
+        let matrix = rxing::MultiFormatWriter
+            .encode("https://example.com", &BarcodeFormat::DATA_MATRIX, 0, 0)
+            .unwrap();
+        let symbol = DynamicImage::from(&matrix).to_luma8();
+        let scaled = image::imageops::resize(
+            &symbol,
+            symbol.width() * 8,
+            symbol.height() * 8,
+            image::imageops::FilterType::Nearest,
+        );
+        let mut padded = GrayImage::from_pixel(scaled.width() + 64, scaled.height() + 64, image::Luma([255]));
+        image::imageops::overlay(&mut padded, &scaled, 32, 32);
+        DynamicImage::ImageLuma8(padded)
+    }
+
+    #[test]
+    fn data_matrix_requires_format_opt_in() {
+        let img = create_test_data_matrix();
+        assert!(try_decode(&img, &TestConfig::default()).is_err());
+
+        let config = TestConfig {
+            formats: vec![SymbolFormat::Qr, SymbolFormat::DataMatrix],
+            ..TestConfig::default()
+        };
+        let result = try_decode(&img, &config).unwrap();
+        assert_eq!(result.content, "https://example.com");
+        assert_eq!(result.format, SymbolFormat::DataMatrix);
+        assert!(result.metadata.is_none());
+    }
+
+    #[test]
+    fn from_barcode_format_rejects_unmapped_formats() {
+        assert_eq!(from_barcode_format(&BarcodeFormat::QR_CODE).unwrap(), SymbolFormat::Qr);
+        assert_eq!(from_barcode_format(&BarcodeFormat::AZTEC).unwrap(), SymbolFormat::Aztec);
+        assert!(matches!(
+            from_barcode_format(&BarcodeFormat::EAN_13),
+            Err(QrScoreError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn qr_not_decoded_when_only_other_formats_requested() {
+        let img = image::load_from_memory(&create_test_qr()).unwrap();
+        let config = TestConfig {
+            formats: vec![SymbolFormat::DataMatrix],
+            ..TestConfig::default()
+        };
+        assert!(try_decode(&img, &config).is_err());
+    }
+
+    #[test]
+    fn decode_reports_qr_format() {
+        let result = multi_decode(&create_test_qr()).unwrap();
+        assert_eq!(result.format, SymbolFormat::Qr);
+    }
+
     #[test]
     fn decode_blank_image_returns_error() {
         let blank = DynamicImage::new_luma8(100, 100);
@@ -8,6 +8,12 @@ pub enum QrScoreError {
     #[error("No QR code found in image")]
     DecodeFailed,
 
+    #[error("QR code found but could not be decoded")]
+    UnreadableSymbol,
+
+    #[error("Decoder panicked")]
+    DecoderPanicked,
+
     #[error("Invalid SVG: {0}")]
     InvalidSvg(String),
 
@@ -23,6 +29,28 @@ pub enum QrScoreError {
 
     #[error("Dimension overflow: {width} x {height} overflows")]
     DimensionOverflow { width: u32, height: u32 },
+
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+
+    #[error("Scoring exceeded the configured timeout")]
+    TimedOut,
+
+    #[error("Crop {width}x{height} at ({x}, {y}) is outside the {image_width}x{image_height} image")]
+    CropOutOfBounds {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        image_width: u32,
+        image_height: u32,
+    },
+
+    #[error("Unknown weight key `{key}`: no stress test by that name")]
+    UnknownWeightKey { key: String },
+
+    #[error("Decoded an unsupported symbology: {0}")]
+    UnsupportedFormat(String),
 }
 
 pub type Result<T> = std::result::Result<T, QrScoreError>;
@@ -45,6 +73,16 @@ mod tests {
         assert!(err.to_string().contains("No QR code"));
     }
 
+    #[test]
+    fn error_display_unreadable_symbol() {
+        assert!(QrScoreError::UnreadableSymbol.to_string().contains("could not be decoded"));
+    }
+
+    #[test]
+    fn error_display_decoder_panicked() {
+        assert!(QrScoreError::DecoderPanicked.to_string().contains("panicked"));
+    }
+
     #[test]
     fn error_display_invalid_svg() {
         let err = QrScoreError::InvalidSvg("bad xml".to_string());
@@ -71,4 +109,37 @@ mod tests {
         let err = QrScoreError::DimensionOverflow { width: u32::MAX, height: u32::MAX };
         assert!(err.to_string().contains("overflow"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn error_display_invalid_config() {
+        let err = QrScoreError::InvalidConfig("grades out of order".to_string());
+        let msg = err.to_string();
+        assert!(msg.contains("Invalid config"));
+        assert!(msg.contains("grades out of order"));
+    }
+
+    #[test]
+    fn error_display_timed_out() {
+        assert!(QrScoreError::TimedOut.to_string().contains("timeout"));
+    }
+
+    #[test]
+    fn error_display_crop_out_of_bounds() {
+        let err = QrScoreError::CropOutOfBounds { x: 50, y: 0, width: 100, height: 100, image_width: 120, image_height: 120 };
+        let msg = err.to_string();
+        assert!(msg.contains("100x100 at (50, 0)"));
+        assert!(msg.contains("120x120"));
+    }
+
+    #[test]
+    fn error_display_unknown_weight_key() {
+        let err = QrScoreError::UnknownWeightKey { key: "downscal_1x".to_string() };
+        assert!(err.to_string().contains("`downscal_1x`"));
+    }
+
+    #[test]
+    fn error_display_unsupported_format() {
+        let err = QrScoreError::UnsupportedFormat("EAN_13".to_string());
+        assert!(err.to_string().contains("EAN_13"));
+    }
+}
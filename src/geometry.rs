@@ -0,0 +1,460 @@
+use crate::types::{BitMatrix, FinderPattern, FinderReport, QuietZoneReport};
+use image::{DynamicImage, GrayImage};
+
+/// A QR symbol located by rqrr, in the input image's pixel space.
+#[derive(Debug, Clone)]
+pub(crate) struct GridLocation {
+    /// Symbol corners: top-left, top-right, bottom-right, bottom-left.
+    pub corners: [(f32, f32); 4],
+    /// Modules per side.
+    pub modules: usize,
+    /// Whether the grid was only found after inverting the image (light-on-dark).
+    pub inverted: bool,
+}
+
+impl GridLocation {
+    /// Average module pitch in pixels along the four symbol edges.
+    pub fn module_size(&self) -> f32 {
+        let c = &self.corners;
+        let edges = [(c[0], c[1]), (c[1], c[2]), (c[2], c[3]), (c[3], c[0])];
+        let perimeter: f32 = edges
+            .iter()
+            .map(|&((x0, y0), (x1, y1))| ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt())
+            .sum();
+        perimeter / (4 * self.modules) as f32
+    }
+
+    /// Image position of a point given in symbol coordinates, where `(0, 0)`
+    /// is the top-left corner and `(1, 1)` the bottom-right. Maps through
+    /// the perspective transform the four corners define.
+    pub fn project(&self, u: f32, v: f32) -> (f32, f32) {
+        let [(x0, y0), (x1, y1), (x2, y2), (x3, y3)] = self.corners;
+        let (dx3, dy3) = (x0 - x1 + x2 - x3, y0 - y1 + y2 - y3);
+        let (g, h) = if dx3.abs() < 1e-6 && dy3.abs() < 1e-6 {
+            (0.0, 0.0)
+        } else {
+            let (dx1, dy1, dx2, dy2) = (x1 - x2, y1 - y2, x3 - x2, y3 - y2);
+            let den = dx1 * dy2 - dx2 * dy1;
+            ((dx3 * dy2 - dx2 * dy3) / den, (dx1 * dy3 - dx3 * dy1) / den)
+        };
+        let w = g * u + h * v + 1.0;
+        (
+            ((x1 - x0 + g * x1) * u + (x3 - x0 + h * x3) * v + x0) / w,
+            ((y1 - y0 + g * y1) * u + (y3 - y0 + h * y3) * v + y0) / w,
+        )
+    }
+
+    /// Image position of the center of the module at `row`, `col`.
+    pub fn module_center(&self, row: usize, col: usize) -> (f32, f32) {
+        let n = self.modules as f32;
+        self.project((col as f32 + 0.5) / n, (row as f32 + 0.5) / n)
+    }
+
+    /// Axis-aligned bounding box as `(min_x, min_y, max_x, max_y)`.
+    pub fn bounding_box(&self) -> (f32, f32, f32, f32) {
+        self.corners.iter().fold(
+            (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+            |(x0, y0, x1, y1), &(x, y)| (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+        )
+    }
+}
+
+/// Symbol corners of an rqrr grid: top-left, top-right, bottom-right, bottom-left.
+pub(crate) fn grid_corners<G: rqrr::BitGrid>(grid: &rqrr::Grid<G>) -> [(f32, f32); 4] {
+    // rqrr reports bounds at grid coordinate `size + 1`, one module past the
+    // symbol edge, so pull each corner back towards the top-left origin.
+    let modules = grid.grid.size() as f32;
+    let origin = grid.bounds[0];
+    let k = modules / (modules + 1.0);
+    grid.bounds.map(|p| {
+        (
+            origin.x as f32 + (p.x - origin.x) as f32 * k,
+            origin.y as f32 + (p.y - origin.y) as f32 * k,
+        )
+    })
+}
+
+fn detect(luma: GrayImage) -> Option<([(f32, f32); 4], usize)> {
+    use rqrr::BitGrid;
+
+    let mut prepared = rqrr::PreparedImage::prepare(luma);
+    let grids = prepared.detect_grids();
+    let grid = grids.first()?;
+    Some((grid_corners(grid), grid.grid.size()))
+}
+
+/// Find the first QR grid, trying the image as-is and then inverted.
+pub(crate) fn locate_grid(img: &DynamicImage) -> Option<GridLocation> {
+    let luma = img.to_luma8();
+    let mut inverted = luma.clone();
+    image::imageops::invert(&mut inverted);
+
+    let located = std::panic::catch_unwind(move || {
+        detect(luma)
+            .map(|found| (found, false))
+            .or_else(|| detect(inverted).map(|found| (found, true)))
+    });
+
+    let ((corners, modules), inverted) = located.ok().flatten()?;
+    Some(GridLocation { corners, modules, inverted })
+}
+
+fn detect_matrix(luma: GrayImage) -> Option<BitMatrix> {
+    use rqrr::BitGrid;
+
+    let mut prepared = rqrr::PreparedImage::prepare(luma);
+    let grids = prepared.detect_grids();
+    let grid = &grids.first()?.grid;
+    let size = grid.size();
+    let bits = (0..size * size).map(|i| grid.bit(i / size, i % size)).collect();
+    Some(BitMatrix { size, bits })
+}
+
+/// The module matrix rqrr samples at the detected grid's module centers,
+/// trying the image as-is and then inverted. `true` is a dark module of the
+/// symbol, so for a light-on-dark code it marks a light pixel.
+///
+/// Only the rqrr geometry path can produce this: codes that rxing decodes but
+/// rqrr can't find a grid for return `None`.
+pub fn extract_matrix(img: &DynamicImage) -> Option<BitMatrix> {
+    let luma = img.to_luma8();
+    let mut inverted = luma.clone();
+    image::imageops::invert(&mut inverted);
+
+    std::panic::catch_unwind(move || detect_matrix(luma).or_else(|| detect_matrix(inverted)))
+        .ok()
+        .flatten()
+}
+
+/// Mean luma (0-255) around each module center of `location`, row-major.
+///
+/// Averages a square aperture 40% of a module wide so a slightly misplaced
+/// center doesn't land on a neighbouring module's edge.
+pub(crate) fn sample_modules(img: &DynamicImage, location: &GridLocation) -> Vec<f32> {
+    let luma = img.to_luma8();
+    let (width, height) = luma.dimensions();
+    let radius = (location.module_size() * 0.2).floor().max(0.0) as i64;
+    let n = location.modules;
+
+    (0..n * n)
+        .map(|i| {
+            let (cx, cy) = location.module_center(i / n, i % n);
+            let (cx, cy) = (cx.floor() as i64, cy.floor() as i64);
+            let (mut sum, mut count) = (0u32, 0u32);
+            for y in (cy - radius)..=(cy + radius) {
+                for x in (cx - radius)..=(cx + radius) {
+                    if (0..width as i64).contains(&x) && (0..height as i64).contains(&y) {
+                        sum += luma.get_pixel(x as u32, y as u32).0[0] as u32;
+                        count += 1;
+                    }
+                }
+            }
+            if count == 0 { 0.0 } else { sum as f32 / count as f32 }
+        })
+        .collect()
+}
+
+/// Ideal finder pattern runs in modules: dark, light, dark center, light, dark.
+const FINDER_RUNS: [f32; 5] = [1.0, 1.0, 3.0, 1.0, 1.0];
+
+/// Measure each of the three finder patterns of the detected QR along its
+/// horizontal and vertical center lines, after binarizing at the midpoint
+/// between the darkest and lightest pixel.
+///
+/// Runs are scaled so the five sum to 7 modules, so a pattern that is merely
+/// larger or smaller than the grid still matches. Returns no finders if the
+/// grid can't be located at all.
+pub fn check_finder_patterns(img: &DynamicImage) -> FinderReport {
+    check_finder_patterns_at(img, locate_grid(img).as_ref())
+}
+
+/// [`check_finder_patterns`] for a grid the caller already located.
+pub(crate) fn check_finder_patterns_at(img: &DynamicImage, location: Option<&GridLocation>) -> FinderReport {
+    let Some(location) = location else {
+        return FinderReport { finders: Vec::new() };
+    };
+
+    let luma = img.to_luma8();
+    let (width, height) = luma.dimensions();
+    let (min, max) = luma
+        .as_raw()
+        .iter()
+        .fold((u8::MAX, u8::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    let threshold = ((min as u16 + max as u16) / 2) as u8;
+    let n = location.modules as f32;
+    let is_dark = |u: f32, v: f32| {
+        let (x, y) = location.project(u / n, v / n);
+        let (x, y) = (x.floor(), y.floor());
+        if x < 0.0 || y < 0.0 || x >= width as f32 || y >= height as f32 {
+            return location.inverted;
+        }
+        (luma.get_pixel(x as u32, y as u32).0[0] <= threshold) != location.inverted
+    };
+
+    // Half-pixel steps, in module units
+    let step = 0.5 / location.module_size().max(0.5);
+    let runs = |(cu, cv): (f32, f32), (du, dv): (f32, f32)| -> [f32; 5] {
+        // Length of consecutive samples of one color walking away from `start`
+        let walk = |start: f32, sign: f32, dark: bool| {
+            let mut t = start;
+            while t - start < 6.0 && is_dark(cu + du * sign * t, cv + dv * sign * t) == dark {
+                t += step;
+            }
+            t
+        };
+        let center_back = walk(0.0, -1.0, true);
+        let center_fwd = walk(0.0, 1.0, true);
+        let light_back = walk(center_back, -1.0, false);
+        let light_fwd = walk(center_fwd, 1.0, false);
+        let dark_back = walk(light_back, -1.0, true);
+        let dark_fwd = walk(light_fwd, 1.0, true);
+        [
+            dark_back - light_back,
+            light_back - center_back,
+            center_back + center_fwd,
+            light_fwd - center_fwd,
+            dark_fwd - light_fwd,
+        ]
+    };
+
+    let finders = [(3.5, 3.5), (n - 3.5, 3.5), (3.5, n - 3.5)]
+        .into_iter()
+        .map(|center| {
+            let horizontal = runs(center, (1.0, 0.0));
+            let vertical = runs(center, (0.0, 1.0));
+            let deviation = finder_deviation(&horizontal).max(finder_deviation(&vertical));
+            FinderPattern {
+                center: location.project(center.0 / n, center.1 / n),
+                horizontal: normalize_runs(&horizontal),
+                vertical: normalize_runs(&vertical),
+                deviation,
+            }
+        })
+        .collect();
+
+    FinderReport { finders }
+}
+
+/// Scale runs so they sum to the 7 modules of an ideal finder.
+fn normalize_runs(runs: &[f32; 5]) -> [f32; 5] {
+    let total: f32 = runs.iter().sum();
+    if total <= 0.0 {
+        return [0.0; 5];
+    }
+    runs.map(|r| r * 7.0 / total)
+}
+
+/// Largest relative difference between a run and its ideal 1:1:3:1:1 width.
+fn finder_deviation(runs: &[f32; 5]) -> f32 {
+    normalize_runs(runs)
+        .iter()
+        .zip(FINDER_RUNS)
+        .map(|(r, ideal)| (r - ideal).abs() / ideal)
+        .fold(0.0, f32::max)
+}
+
+/// Measure the light margin on each side of the QR, in module widths.
+///
+/// The margin on a side is the shortest run of background pixels between the
+/// symbol edge and the first foreground pixel (or the image border), taken
+/// across every row or column the symbol spans. Pixel runs are converted to
+/// modules using the detected grid's module pitch.
+pub fn measure_quiet_zone(img: &DynamicImage) -> Option<QuietZoneReport> {
+    measure_quiet_zone_at(img, locate_grid(img).as_ref())
+}
+
+/// [`measure_quiet_zone`] for a grid the caller already located.
+pub(crate) fn measure_quiet_zone_at(img: &DynamicImage, location: Option<&GridLocation>) -> Option<QuietZoneReport> {
+    let location = location?;
+    let module_size = location.module_size();
+    if module_size <= 0.0 {
+        return None;
+    }
+
+    let luma = img.to_luma8();
+    let (width, height) = luma.dimensions();
+    let (min, max) = luma
+        .as_raw()
+        .iter()
+        .fold((u8::MAX, u8::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    let threshold = ((min as u16 + max as u16) / 2) as u8;
+    let is_background = |x: u32, y: u32| (luma.get_pixel(x, y).0[0] > threshold) != location.inverted;
+
+    let (bx0, by0, bx1, by1) = location.bounding_box();
+    let x0 = (bx0.round().max(0.0) as u32).min(width);
+    let y0 = (by0.round().max(0.0) as u32).min(height);
+    let x1 = (bx1.round().max(0.0) as u32).min(width);
+    let y1 = (by1.round().max(0.0) as u32).min(height);
+    if x0 >= x1 || y0 >= y1 {
+        return None;
+    }
+
+    let run = |pixels: &mut dyn Iterator<Item = (u32, u32)>| {
+        pixels.take_while(|&(x, y)| is_background(x, y)).count() as u32
+    };
+
+    let top = (x0..x1).map(|x| run(&mut (0..y0).rev().map(|y| (x, y)))).min()?;
+    let bottom = (x0..x1).map(|x| run(&mut (y1..height).map(|y| (x, y)))).min()?;
+    let left = (y0..y1).map(|y| run(&mut (0..x0).rev().map(|x| (x, y)))).min()?;
+    let right = (y0..y1).map(|y| run(&mut (x1..width).map(|x| (x, y)))).min()?;
+
+    let to_modules = |px: u32| px as f32 / module_size;
+    let (top, right, bottom, left) = (to_modules(top), to_modules(right), to_modules(bottom), to_modules(left));
+
+    Some(QuietZoneReport {
+        top,
+        right,
+        bottom,
+        left,
+        min: top.min(right).min(bottom).min(left),
+        module_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    fn qr_with_quiet_zone(quiet_zone: bool) -> DynamicImage {
+        let code = qrcode::QrCode::new(b"https://example.com").unwrap();
+        let img = code
+            .render::<Luma<u8>>()
+            .quiet_zone(quiet_zone)
+            .module_dimensions(8, 8)
+            .build();
+        DynamicImage::ImageLuma8(img)
+    }
+
+    #[test]
+    fn locate_grid_finds_symbol_corners() {
+        let location = locate_grid(&qr_with_quiet_zone(true)).unwrap();
+        assert_eq!(location.modules, 25);
+        assert!(!location.inverted);
+        assert!((location.module_size() - 8.0).abs() < 0.5);
+        let (x0, y0, x1, y1) = location.bounding_box();
+        assert!((x0 - 32.0).abs() <= 1.0 && (y0 - 32.0).abs() <= 1.0);
+        assert!((x1 - 232.0).abs() <= 1.0 && (y1 - 232.0).abs() <= 1.0);
+    }
+
+    #[test]
+    fn locate_grid_handles_inverted() {
+        let mut img = qr_with_quiet_zone(true).to_luma8();
+        image::imageops::invert(&mut img);
+        let location = locate_grid(&DynamicImage::ImageLuma8(img)).unwrap();
+        assert!(location.inverted);
+    }
+
+    #[test]
+    fn module_centers_follow_grid() {
+        let location = locate_grid(&qr_with_quiet_zone(true)).unwrap();
+        let (x, y) = location.module_center(0, 0);
+        assert!((x - 36.0).abs() < 1.0 && (y - 36.0).abs() < 1.0, "({}, {})", x, y);
+        let (x, y) = location.module_center(24, 24);
+        assert!((x - 228.0).abs() < 1.0 && (y - 228.0).abs() < 1.0, "({}, {})", x, y);
+    }
+
+    #[test]
+    fn project_handles_perspective_quad() {
+        let location = GridLocation {
+            corners: [(0.0, 0.0), (100.0, 10.0), (90.0, 90.0), (5.0, 100.0)],
+            modules: 21,
+            inverted: false,
+        };
+        for (i, &(u, v)) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)].iter().enumerate() {
+            let (x, y) = location.project(u, v);
+            let (ex, ey) = location.corners[i];
+            assert!((x - ex).abs() < 1e-3 && (y - ey).abs() < 1e-3, "corner {}: ({}, {})", i, x, y);
+        }
+    }
+
+    #[test]
+    fn sample_modules_reads_finder_pattern() {
+        let img = qr_with_quiet_zone(true);
+        let location = locate_grid(&img).unwrap();
+        let samples = sample_modules(&img, &location);
+        assert_eq!(samples.len(), 25 * 25);
+        // Finder: dark outer ring, light ring, dark 3x3 center
+        assert!(samples[0] < 10.0);
+        assert!(samples[25 + 1] > 245.0);
+        assert!(samples[3 * 25 + 3] < 10.0);
+    }
+
+    #[test]
+    fn extract_matrix_matches_encoded_modules() {
+        let code = qrcode::QrCode::new(b"https://example.com").unwrap();
+        let expected: Vec<bool> = code.to_colors().iter().map(|&c| c == qrcode::Color::Dark).collect();
+
+        let matrix = extract_matrix(&qr_with_quiet_zone(true)).unwrap();
+        assert_eq!(matrix.size, 25);
+        assert_eq!(matrix.bits, expected);
+        assert!(matrix.get(0, 0));
+        assert!(!matrix.get(1, 1));
+
+        let mut inverted = qr_with_quiet_zone(true).to_luma8();
+        image::imageops::invert(&mut inverted);
+        assert_eq!(extract_matrix(&DynamicImage::ImageLuma8(inverted)).unwrap().bits, expected);
+    }
+
+    #[test]
+    fn extract_matrix_without_grid_is_none() {
+        assert!(extract_matrix(&DynamicImage::new_luma8(100, 100)).is_none());
+    }
+
+    #[test]
+    fn finder_patterns_clean_render_match_ideal() {
+        let report = check_finder_patterns(&qr_with_quiet_zone(true));
+        assert_eq!(report.finders.len(), 3);
+        let worst = report.worst_deviation().unwrap();
+        assert!(worst < 0.1, "clean finders deviate by {}", worst);
+        assert!(report.passes(0.3));
+        let (x, y) = report.finders[0].center;
+        assert!((x - 60.0).abs() < 1.5 && (y - 60.0).abs() < 1.5, "({}, {})", x, y);
+    }
+
+    #[test]
+    fn finder_patterns_detect_distorted_finder() {
+        // Thicken the top-left finder's inner light ring into its dark center
+        let mut img = qr_with_quiet_zone(true).to_luma8();
+        for y in 56..64 {
+            for x in 48..72 {
+                img.put_pixel(x, y, Luma([255]));
+            }
+        }
+        let report = check_finder_patterns(&DynamicImage::ImageLuma8(img));
+        assert_eq!(report.finders.len(), 3);
+        assert!(report.finders[0].deviation > 0.3, "deviation {}", report.finders[0].deviation);
+        assert!(report.finders[1].deviation < 0.1);
+        assert!(!report.passes(0.3));
+    }
+
+    #[test]
+    fn finder_patterns_blank_image_has_none() {
+        let report = check_finder_patterns(&DynamicImage::new_luma8(100, 100));
+        assert!(report.finders.is_empty());
+        assert!(!report.passes(1.0));
+    }
+
+    #[test]
+    fn quiet_zone_standard_render_is_four_modules() {
+        let report = measure_quiet_zone(&qr_with_quiet_zone(true)).unwrap();
+        assert!((report.min - 4.0).abs() < 0.25, "expected ~4 modules, got {:?}", report);
+        assert!((report.top - report.bottom).abs() < 0.25);
+        assert!((report.left - report.right).abs() < 0.25);
+    }
+
+    #[test]
+    fn quiet_zone_missing_is_zero() {
+        // Without a margin rqrr can't find the finders, so pad by one module.
+        let bare = qr_with_quiet_zone(false).to_luma8();
+        let mut padded = GrayImage::from_pixel(bare.width() + 8, bare.height() + 8, Luma([255]));
+        image::imageops::overlay(&mut padded, &bare, 8, 8);
+        let report = measure_quiet_zone(&DynamicImage::ImageLuma8(padded)).unwrap();
+        assert!(report.min < 1.5, "expected ~1 module, got {:?}", report);
+    }
+
+    #[test]
+    fn quiet_zone_blank_image_is_none() {
+        assert!(measure_quiet_zone(&DynamicImage::new_luma8(100, 100)).is_none());
+    }
+}
@@ -0,0 +1,132 @@
+use crate::decoder::try_decode;
+use crate::geometry::{locate_grid, sample_modules};
+use crate::types::{Iso15415Report, IsoGrade, TestConfig};
+use image::DynamicImage;
+
+/// Grade `img` on the ISO/IEC 15415 decode, symbol contrast, minimum
+/// reflectance and modulation parameters.
+///
+/// Reflectance is taken as 8-bit luma / 255 at each module center of the
+/// rqrr-detected grid, standing in for a calibrated verifier's measurement.
+/// Modulation is graded on the worst single module rather than per codeword
+/// with error correction headroom, so it is stricter than the standard.
+/// Without a detectable grid every parameter is F.
+pub fn iso15415_grade(img: &DynamicImage) -> Iso15415Report {
+    let decode = if try_decode(img, &TestConfig::default()).is_ok() { IsoGrade::A } else { IsoGrade::F };
+
+    let samples = locate_grid(img)
+        .map(|location| sample_modules(img, &location))
+        .unwrap_or_default();
+    if samples.is_empty() {
+        return Iso15415Report {
+            decode,
+            symbol_contrast: 0.0,
+            symbol_contrast_grade: IsoGrade::F,
+            min_reflectance: 0.0,
+            max_reflectance: 0.0,
+            min_reflectance_grade: IsoGrade::F,
+            modulation: 0.0,
+            modulation_grade: IsoGrade::F,
+            overall: IsoGrade::F,
+        };
+    }
+
+    let reflectance: Vec<f32> = samples.iter().map(|v| v / 255.0).collect();
+    let r_min = reflectance.iter().copied().fold(f32::MAX, f32::min);
+    let r_max = reflectance.iter().copied().fold(f32::MIN, f32::max);
+    let symbol_contrast = r_max - r_min;
+    let global_threshold = (r_max + r_min) / 2.0;
+
+    let modulation = if symbol_contrast > 0.0 {
+        reflectance
+            .iter()
+            .map(|r| 2.0 * (r - global_threshold).abs() / symbol_contrast)
+            .fold(f32::MAX, f32::min)
+    } else {
+        0.0
+    };
+
+    let symbol_contrast_grade = grade_by(symbol_contrast, [0.70, 0.55, 0.40, 0.20]);
+    let min_reflectance_grade = if r_min <= 0.5 * r_max { IsoGrade::A } else { IsoGrade::F };
+    let modulation_grade = grade_by(modulation, [0.50, 0.40, 0.30, 0.20]);
+    let overall = decode
+        .min(symbol_contrast_grade)
+        .min(min_reflectance_grade)
+        .min(modulation_grade);
+
+    Iso15415Report {
+        decode,
+        symbol_contrast,
+        symbol_contrast_grade,
+        min_reflectance: r_min,
+        max_reflectance: r_max,
+        min_reflectance_grade,
+        modulation,
+        modulation_grade,
+        overall,
+    }
+}
+
+/// Grade `value` against the minimums for A, B, C and D.
+fn grade_by(value: f32, [a, b, c, d]: [f32; 4]) -> IsoGrade {
+    match value {
+        v if v >= a => IsoGrade::A,
+        v if v >= b => IsoGrade::B,
+        v if v >= c => IsoGrade::C,
+        v if v >= d => IsoGrade::D,
+        _ => IsoGrade::F,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    fn qr_with_levels(dark: u8, light: u8) -> DynamicImage {
+        let code = qrcode::QrCode::new(b"https://example.com").unwrap();
+        let img = code.render::<Luma<u8>>().dark_color(Luma([dark])).light_color(Luma([light])).build();
+        DynamicImage::ImageLuma8(img)
+    }
+
+    #[test]
+    fn clean_qr_grades_a() {
+        let report = iso15415_grade(&qr_with_levels(0, 255));
+        assert_eq!(report.overall, IsoGrade::A);
+        assert!((report.symbol_contrast - 1.0).abs() < 0.01);
+        assert!(report.modulation > 0.9);
+    }
+
+    #[test]
+    fn low_contrast_qr_grades_down() {
+        let report = iso15415_grade(&qr_with_levels(100, 160));
+        assert_eq!(report.decode, IsoGrade::A);
+        assert_eq!(report.symbol_contrast_grade, IsoGrade::D);
+        // 100/255 is more than half of 160/255
+        assert_eq!(report.min_reflectance_grade, IsoGrade::F);
+        assert_eq!(report.overall, IsoGrade::F);
+    }
+
+    #[test]
+    fn mid_contrast_grades_b() {
+        let report = iso15415_grade(&qr_with_levels(40, 200));
+        assert_eq!(report.symbol_contrast_grade, IsoGrade::B);
+        assert_eq!(report.min_reflectance_grade, IsoGrade::A);
+        assert_eq!(report.overall, IsoGrade::B);
+    }
+
+    #[test]
+    fn blank_image_fails_everything() {
+        let report = iso15415_grade(&DynamicImage::new_luma8(100, 100));
+        assert_eq!(report.decode, IsoGrade::F);
+        assert_eq!(report.overall, IsoGrade::F);
+    }
+
+    #[test]
+    fn grade_value_and_order() {
+        assert_eq!(IsoGrade::A.value(), 4.0);
+        assert_eq!(IsoGrade::F.value(), 0.0);
+        assert!(IsoGrade::B > IsoGrade::C);
+        assert_eq!(grade_by(0.45, [0.70, 0.55, 0.40, 0.20]), IsoGrade::C);
+    }
+}
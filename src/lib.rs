@@ -1,25 +1,63 @@
 pub mod decoder;
 pub mod error;
+pub mod geometry;
+pub mod grading;
+pub mod lint;
+#[cfg(feature = "svg")]
 pub mod render;
+pub mod report;
 pub mod scorer;
+pub mod sizing;
 pub mod types;
 
 pub use error::{QrScoreError, Result};
+pub use lint::lint_config;
 pub use types::{
-    DecodeResult, ErrorCorrectionLevel, QrMetadata, StressResults, TestConfig, ValidationResult,
+    BitMatrix,
+    CompareResult,
+    ConfigIssue,
+    DecodeResult,
+    DecoderKind,
+    Diagnostics,
+    ErrorCorrectionLevel,
+    ErrorReport,
+    FinderPattern,
+    FinderReport,
+    FramePolicy,
+    GradeThresholds,
+    Iso15415Report,
+    IsoGrade,
+    IssueSeverity,
+    Polarity,
+    QrMetadata,
+    QuietZoneReport,
+    RenderInfo,
+    ScoreReport,
+    StressResults,
+    SvgDiagnostics,
+    SymbolFormat,
+    TestConfig,
+    TestConfigBuilder,
+    TestOutcome,
+    ValidationResult,
     Weights,
 };
 
-use image::GenericImageView;
+use image::{DynamicImage, GenericImageView};
+use std::io::Read;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
 
 const MAX_DIMENSION: u32 = 10_000;
+pub(crate) const MAX_FRAMES: usize = 100;
 
-fn validate_dimensions(width: u32, height: u32) -> Result<()> {
-    if width > MAX_DIMENSION || height > MAX_DIMENSION {
+pub(crate) fn validate_dimensions(width: u32, height: u32, max_dimension: Option<u32>) -> Result<()> {
+    let max_dimension = max_dimension.unwrap_or(MAX_DIMENSION);
+    if width > max_dimension || height > max_dimension {
         return Err(QrScoreError::DimensionsTooLarge {
             width,
             height,
-            max_dimension: MAX_DIMENSION,
+            max_dimension,
         });
     }
     if width.checked_mul(height).is_none() {
@@ -29,28 +67,218 @@ fn validate_dimensions(width: u32, height: u32) -> Result<()> {
 }
 
 pub fn validate(image_bytes: &[u8], config: &TestConfig) -> Result<ValidationResult> {
-    let img = image::load_from_memory(image_bytes)
-        .map_err(|e| QrScoreError::ImageLoad(e.to_string()))?;
+    let Some(timeout) = config.timeout else {
+        return validate_until(image_bytes, config, None);
+    };
+    let deadline = Instant::now() + timeout;
+    let (image_bytes, config) = (image_bytes.to_vec(), config.clone());
+    with_deadline(deadline, move || validate_until(&image_bytes, &config, Some(deadline)))
+}
+
+/// [`validate`] for an image that's already loaded, skipping the
+/// `image::load_from_memory` pass. Cropping, the dimension limit, decoding
+/// and the stress tests all apply as usual. EXIF orientation is the
+/// caller's to apply, and `frame_policy` has no effect on a single image.
+pub fn validate_image(img: &DynamicImage, config: &TestConfig) -> Result<ValidationResult> {
+    let Some(timeout) = config.timeout else {
+        return validate_image_until(img, config, None);
+    };
+    let deadline = Instant::now() + timeout;
+    let (img, config) = (img.clone(), config.clone());
+    with_deadline(deadline, move || validate_image_until(&img, &config, Some(deadline)))
+}
+
+fn validate_image_until(
+    img: &DynamicImage,
+    config: &TestConfig,
+    deadline: Option<Instant>,
+) -> Result<ValidationResult> {
+    validate_config(config)?;
+    score_frame(img, config, deadline)
+}
+
+/// Config checks that don't depend on the input.
+fn validate_config(config: &TestConfig) -> Result<()> {
+    scorer::validate_weights(config)?;
+    if config.formats.is_empty() {
+        return Err(QrScoreError::InvalidConfig("formats must list at least one symbology".into()));
+    }
+    if config.decoders.is_empty() {
+        return Err(QrScoreError::InvalidConfig("decoders must list at least one decoder".into()));
+    }
+    config.grades.validate()
+}
+
+pub(crate) fn validate_until(
+    image_bytes: &[u8],
+    config: &TestConfig,
+    deadline: Option<Instant>,
+) -> Result<ValidationResult> {
+    validate_config(config)?;
+
+    let frames = decoder::load_frames(image_bytes, config)?;
+
+    let mut best: Option<ValidationResult> = None;
+    let mut first_error = None;
+    for (index, frame) in frames.into_iter().enumerate() {
+        check_deadline(deadline)?;
+        match score_frame(&frame, config, deadline) {
+            Ok(result) if config.frame_policy == FramePolicy::FirstDecodable => {
+                return Ok(ValidationResult { frame: index, ..result });
+            }
+            Ok(result) => {
+                if best.as_ref().is_none_or(|b| result.score > b.score) {
+                    best = Some(ValidationResult { frame: index, ..result });
+                }
+            }
+            Err(QrScoreError::TimedOut) => return Err(QrScoreError::TimedOut),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    best.ok_or_else(|| first_error.unwrap_or(QrScoreError::DecodeFailed))
+}
+
+/// Crop, check, decode and stress-test one loaded frame.
+fn score_frame(img: &DynamicImage, config: &TestConfig, deadline: Option<Instant>) -> Result<ValidationResult> {
+    let cropped;
+    let img = match config.crop {
+        Some(rect) => {
+            cropped = crop_image(img, rect)?;
+            &cropped
+        }
+        None => img,
+    };
 
     let (width, height) = img.dimensions();
-    validate_dimensions(width, height)?;
+    validate_dimensions(width, height, config.max_dimension)?;
+
+    check_deadline(deadline)?;
+    let decode_start = config.collect_diagnostics.then(Instant::now);
+    let mut decode_result = decoder::try_decode(img, config)?;
+    let location = decoder::locate_symbol(img, decode_result.format);
+    decoder::fill_version(&mut decode_result, location.as_ref());
+
+    let stress_start = config.collect_diagnostics.then(Instant::now);
+    let (stress_results, score) =
+        scorer::validate_until(img, config, decode_result.format, location.as_ref(), deadline)?;
 
-    let decode_result = decoder::try_decode(&img)?;
-    let (stress_results, score) = scorer::validate(&img, config);
+    let diagnostics = decode_start.zip(stress_start).map(|(decode_start, stress_start)| Diagnostics {
+        render_ms: None,
+        decode_ms: millis(stress_start - decode_start),
+        stress_ms: millis(stress_start.elapsed()),
+        render: None,
+        upscale_retry: false,
+    });
+
+    let mut grade = config.grades.grade_of(score);
+    if config.min_contrast_floor.is_some_and(|floor| stress_results.contrast_ratio < floor) {
+        // IsoGrade orders F lowest, so min keeps the worse grade
+        grade = grade.min(config.contrast_floor_grade);
+    }
 
     Ok(ValidationResult {
         score,
+        grade: grade.letter().to_string(),
         decodable: true,
         content: Some(decode_result.content),
         metadata: decode_result.metadata,
         stress_results,
+        frame: 0,
+        diagnostics,
+        svg: None,
     })
 }
 
+/// Cut `(x, y, width, height)` out of `img`, rejecting empty rectangles and
+/// any that reach past the image edge.
+pub(crate) fn crop_image(img: &DynamicImage, (x, y, width, height): (u32, u32, u32, u32)) -> Result<DynamicImage> {
+    let (image_width, image_height) = img.dimensions();
+    let fits = |start: u32, len: u32, max: u32| len > 0 && start.checked_add(len).is_some_and(|end| end <= max);
+    if !fits(x, width, image_width) || !fits(y, height, image_height) {
+        return Err(QrScoreError::CropOutOfBounds { x, y, width, height, image_width, image_height });
+    }
+    Ok(img.crop_imm(x, y, width, height))
+}
+
+/// Run `work` on its own thread and return [`QrScoreError::TimedOut`] if it
+/// hasn't finished by `deadline`.
+///
+/// The thread is abandoned rather than killed: it keeps its CPU, and any
+/// rayon workers it is using, until `work` returns. `work` must therefore
+/// check the deadline itself with [`check_deadline`]. Scoring does so before
+/// each frame, the original decode, each variant's transform and decode, and
+/// each SVG render, so an abandoned thread finishes at most one of those
+/// steps (a resvg render or a single decode can't be interrupted) before it
+/// exits.
+pub(crate) fn with_deadline<T, F>(deadline: Instant, work: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(work());
+    });
+    match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => Err(QrScoreError::TimedOut),
+        // The worker panicked before sending
+        Err(RecvTimeoutError::Disconnected) => Err(QrScoreError::DecoderPanicked),
+    }
+}
+
+/// [`QrScoreError::TimedOut`] once `deadline` has passed.
+pub(crate) fn check_deadline(deadline: Option<Instant>) -> Result<()> {
+    match deadline {
+        Some(d) if Instant::now() >= d => Err(QrScoreError::TimedOut),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Score a baseline image `a` and a candidate `b` with the same config and
+/// report which stress tests `b` newly passes or fails. An image that can't
+/// be scored at all (e.g. undecodable) counts as score 0.
+pub fn compare(a: &[u8], b: &[u8], config: &TestConfig) -> CompareResult {
+    let baseline = validate(a, config).ok();
+    let candidate = validate(b, config).ok();
+    CompareResult::between(baseline.as_ref(), candidate.as_ref())
+}
+
 pub fn decode_only(image_bytes: &[u8]) -> Result<DecodeResult> {
     decoder::multi_decode(image_bytes)
 }
 
+/// Decode each frame of an animated GIF, APNG or WebP, returning the frames
+/// that decoded. Still images yield at most one result.
+pub fn decode_frames(image_bytes: &[u8]) -> Result<Vec<DecodeResult>> {
+    decoder::decode_frames(image_bytes)
+}
+
+fn read_all<R: Read>(mut reader: R) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    reader
+        .read_to_end(&mut buf)
+        .map_err(|e| QrScoreError::ImageLoad(e.to_string()))?;
+    Ok(buf)
+}
+
+/// Like [`validate`], but reads the encoded image from any [`Read`] source.
+pub fn validate_from_reader<R: Read>(reader: R, config: &TestConfig) -> Result<ValidationResult> {
+    validate(&read_all(reader)?, config)
+}
+
+/// Like [`decode_only`], but reads the encoded image from any [`Read`] source.
+pub fn decode_from_reader<R: Read>(reader: R) -> Result<DecodeResult> {
+    decode_only(&read_all(reader)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,6 +318,174 @@ mod tests {
         assert!(result.stress_results.contrast_ratio > 0.0);
     }
 
+    #[test]
+    fn diagnostics_only_when_requested() {
+        let qr_bytes = create_test_qr();
+        let plain = validate(&qr_bytes, &TestConfig::default()).unwrap();
+        assert!(plain.diagnostics.is_none());
+
+        let config = TestConfig::builder().collect_diagnostics(true).build();
+        let timed = validate(&qr_bytes, &config).unwrap();
+        let diagnostics = timed.diagnostics.unwrap();
+        assert!(diagnostics.render_ms.is_none());
+        assert!(diagnostics.decode_ms > 0.0 && diagnostics.stress_ms > 0.0);
+        assert_eq!(timed.score, plain.score);
+    }
+
+    #[test]
+    fn compare_same_image_has_no_deltas() {
+        let qr_bytes = create_test_qr();
+        let diff = compare(&qr_bytes, &qr_bytes, &TestConfig::default());
+        assert_eq!(diff.score_delta, 0);
+        assert!(diff.regressed.is_empty() && diff.newly_passing.is_empty());
+    }
+
+    #[test]
+    fn compare_against_blank_image_regresses() {
+        let blank = DynamicImage::new_luma8(100, 100);
+        let mut blank_bytes = Vec::new();
+        blank.write_to(&mut std::io::Cursor::new(&mut blank_bytes), image::ImageFormat::Png).unwrap();
+
+        let diff = compare(&create_test_qr(), &blank_bytes, &TestConfig::default());
+        assert!(diff.has_regressions());
+        assert!(diff.score_delta < 0);
+        assert_eq!(diff.candidate_score, 0);
+    }
+
+    fn qr_in_corner_of_label() -> (Vec<u8>, (u32, u32, u32, u32)) {
+        let qr = image::load_from_memory(&create_test_qr()).unwrap().to_luma8();
+        let mut label = image::GrayImage::from_pixel(qr.width() * 3, qr.height() * 2, Luma([255]));
+        // Dark artwork elsewhere on the label
+        for (x, y, px) in label.enumerate_pixels_mut() {
+            if x > qr.width() * 2 && y > qr.height() {
+                *px = Luma([if (x / 7 + y / 5) % 2 == 0 { 0 } else { 90 }]);
+            }
+        }
+        image::imageops::overlay(&mut label, &qr, 0, 0);
+
+        let mut buf = Vec::new();
+        DynamicImage::ImageLuma8(label)
+            .write_to(&mut std::io::Cursor::new(&mut buf), image::ImageFormat::Png)
+            .unwrap();
+        (buf, (0, 0, qr.width(), qr.height()))
+    }
+
+    #[test]
+    fn validate_crop_scores_only_the_region() {
+        let (label, (x, y, w, h)) = qr_in_corner_of_label();
+        let config = TestConfig::builder().crop(x, y, w, h).build();
+        let cropped = validate(&label, &config).unwrap();
+        let direct = validate(&create_test_qr(), &TestConfig::default()).unwrap();
+        assert_eq!(cropped.score, direct.score);
+        assert_eq!(cropped.stress_results.contrast_ratio, direct.stress_results.contrast_ratio);
+    }
+
+    #[test]
+    fn validate_crop_out_of_bounds_is_error() {
+        let qr_bytes = create_test_qr();
+        for crop in [(200, 0, 100, 100), (0, 0, 0, 10), (u32::MAX, 0, 2, 2)] {
+            let config = TestConfig { crop: Some(crop), ..TestConfig::default() };
+            let err = validate(&qr_bytes, &config).unwrap_err();
+            assert!(matches!(err, QrScoreError::CropOutOfBounds { .. }), "{:?} gave {:?}", crop, err);
+        }
+    }
+
+    fn animated_gif(frames: &[DynamicImage]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        {
+            let mut encoder = image::codecs::gif::GifEncoder::new(&mut buf);
+            encoder
+                .encode_frames(frames.iter().map(|f| image::Frame::new(f.to_rgba8())))
+                .unwrap();
+        }
+        buf
+    }
+
+    #[test]
+    fn validate_animated_frame_policies() {
+        let sharp = image::load_from_memory(&create_test_qr()).unwrap().to_luma8();
+        let blank = image::GrayImage::from_pixel(sharp.width(), sharp.height(), Luma([255]));
+        // Decodable, but loses most stress tests
+        let mut faint = sharp.clone();
+        for px in faint.pixels_mut() {
+            px.0[0] = if px.0[0] < 128 { 120 } else { 150 };
+        }
+        let gif = animated_gif(&[blank, faint, sharp].map(DynamicImage::ImageLuma8));
+
+        let first = validate(&gif, &TestConfig::default()).unwrap();
+        assert_eq!(first.frame, 1);
+
+        let config = TestConfig { frame_policy: FramePolicy::BestScore, ..TestConfig::default() };
+        let best = validate(&gif, &config).unwrap();
+        assert_eq!(best.frame, 2);
+        assert!(best.score > first.score);
+    }
+
+    #[test]
+    fn validate_image_matches_validate() {
+        let qr_bytes = create_test_qr();
+        let img = image::load_from_memory(&qr_bytes).unwrap();
+        let config = TestConfig::default();
+        let from_image = validate_image(&img, &config).unwrap();
+        let from_bytes = validate(&qr_bytes, &config).unwrap();
+        assert_eq!(from_image.score, from_bytes.score);
+        assert_eq!(from_image.content, from_bytes.content);
+        assert_eq!(from_image.stress_results.tests, from_bytes.stress_results.tests);
+    }
+
+    #[test]
+    fn validate_image_checks_config_and_limits() {
+        let img = image::load_from_memory(&create_test_qr()).unwrap();
+        let config = TestConfig::builder().max_dimension(100).build();
+        assert!(matches!(validate_image(&img, &config), Err(QrScoreError::DimensionsTooLarge { .. })));
+
+        let config = TestConfig::builder().weight("blur_extreme", 1).build();
+        assert!(matches!(validate_image(&img, &config), Err(QrScoreError::UnknownWeightKey { .. })));
+
+        let config = TestConfig::builder().timeout(Duration::ZERO).build();
+        assert!(matches!(validate_image(&img, &config), Err(QrScoreError::TimedOut)));
+
+        let blank = DynamicImage::new_luma8(100, 100);
+        assert!(validate_image(&blank, &TestConfig::default()).is_err());
+    }
+
+    #[test]
+    fn validate_times_out() {
+        let config = TestConfig::builder().timeout(Duration::ZERO).build();
+        let err = validate(&create_test_qr(), &config).unwrap_err();
+        assert!(matches!(err, QrScoreError::TimedOut));
+    }
+
+    #[test]
+    fn validate_within_timeout_matches_untimed() {
+        let qr_bytes = create_test_qr();
+        let config = TestConfig::builder().timeout(Duration::from_secs(60)).build();
+        let timed = validate(&qr_bytes, &config).unwrap();
+        assert_eq!(timed.score, validate(&qr_bytes, &TestConfig::default()).unwrap().score);
+    }
+
+    #[test]
+    fn with_deadline_reports_worker_panic() {
+        let result: Result<()> = with_deadline(Instant::now() + Duration::from_secs(5), || panic!("boom"));
+        assert!(matches!(result, Err(QrScoreError::DecoderPanicked)));
+    }
+
+    #[test]
+    fn validate_rejects_misspelled_weight() {
+        let config = TestConfig::builder().weight("downscal_1x", 1).build();
+        let err = validate(&create_test_qr(), &config).unwrap_err();
+        assert!(matches!(err, QrScoreError::UnknownWeightKey { key } if key == "downscal_1x"));
+    }
+
+    #[test]
+    fn validate_accepts_partial_weights_table() {
+        // A table written before gamma, lighting and the rest existed
+        let config: TestConfig = toml::from_str("[weights]\ndownscale_2x = 3\nblur_heavy = 2\ncontrast_ratio = 70\n").unwrap();
+        let result = validate(&create_test_qr(), &config).unwrap();
+        assert!(result.stress_results.tests.contains_key("gamma_up"));
+        assert!(result.score > 0);
+    }
+
     #[test]
     fn validate_oversized_image_rejected() {
         // Build a 1x1 PNG then lie about dimensions via a crafted config — instead,
@@ -107,6 +503,112 @@ mod tests {
         assert!(!matches!(result.unwrap_err(), QrScoreError::DimensionsTooLarge { .. }));
     }
 
+    #[test]
+    fn validate_dimensions_uses_default_limit() {
+        assert!(validate_dimensions(MAX_DIMENSION, MAX_DIMENSION, None).is_ok());
+        let err = validate_dimensions(MAX_DIMENSION + 1, 1, None).unwrap_err();
+        assert!(matches!(
+            err,
+            QrScoreError::DimensionsTooLarge { max_dimension: MAX_DIMENSION, .. }
+        ));
+    }
+
+    #[test]
+    fn validate_dimensions_honors_configured_limit() {
+        assert!(validate_dimensions(15_000, 15_000, Some(20_000)).is_ok());
+        let err = validate_dimensions(4_001, 10, Some(4_000)).unwrap_err();
+        assert!(matches!(
+            err,
+            QrScoreError::DimensionsTooLarge { width: 4_001, height: 10, max_dimension: 4_000 }
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_image_over_configured_limit() {
+        let qr_bytes = create_test_qr();
+        let config = TestConfig {
+            max_dimension: Some(50),
+            ..TestConfig::default()
+        };
+        let result = validate(&qr_bytes, &config);
+        assert!(matches!(
+            result.unwrap_err(),
+            QrScoreError::DimensionsTooLarge { max_dimension: 50, .. }
+        ));
+    }
+
+    #[test]
+    fn validate_grade_uses_configured_thresholds() {
+        let qr_bytes = create_test_qr();
+        let config = TestConfig::default();
+        let result = validate(&qr_bytes, &config).unwrap();
+        assert_eq!(result.grade, config.grades.grade_from_score(result.score));
+
+        let strict = TestConfig {
+            grades: GradeThresholds { a: 101, b: 100, c: 99, d: 98 },
+            ..TestConfig::default()
+        };
+        let result = validate(&qr_bytes, &strict).unwrap();
+        assert_ne!(result.grade, "A");
+    }
+
+    #[test]
+    fn validate_contrast_floor_caps_grade_not_score() {
+        let qr_bytes = create_test_qr();
+        let unfloored = validate(&qr_bytes, &TestConfig::default()).unwrap();
+        assert_eq!(unfloored.grade, "A");
+
+        let floored = validate(&qr_bytes, &TestConfig::builder().min_contrast_floor(1.1).build()).unwrap();
+        assert_eq!(floored.grade, "F");
+        assert_eq!(floored.score, unfloored.score);
+
+        let capped = TestConfig::builder().min_contrast_floor(1.1).contrast_floor_grade(IsoGrade::C).build();
+        assert_eq!(validate(&qr_bytes, &capped).unwrap().grade, "C");
+
+        let met = TestConfig::builder().min_contrast_floor(0.5).build();
+        assert_eq!(validate(&qr_bytes, &met).unwrap().grade, "A");
+    }
+
+    #[test]
+    fn validate_rejects_empty_formats_and_decoders() {
+        let qr_bytes = create_test_qr();
+        let no_formats = TestConfig::builder().formats(Vec::new()).build();
+        assert!(matches!(validate(&qr_bytes, &no_formats), Err(QrScoreError::InvalidConfig(_))));
+        let no_decoders = TestConfig::builder().decoders(Vec::new()).build();
+        assert!(matches!(validate(&qr_bytes, &no_decoders), Err(QrScoreError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn validate_non_qr_skips_qr_only_checks() {
+        use rxing::Writer;
+        let matrix = rxing::MultiFormatWriter
+            .encode("https://example.com", &rxing::BarcodeFormat::DATA_MATRIX, 0, 0)
+            .unwrap();
+        let symbol = DynamicImage::from(&matrix).to_luma8();
+        let scaled = image::imageops::resize(&symbol, symbol.width() * 8, symbol.height() * 8, image::imageops::FilterType::Nearest);
+        let mut padded = image::GrayImage::from_pixel(scaled.width() + 64, scaled.height() + 64, Luma([255]));
+        image::imageops::overlay(&mut padded, &scaled, 32, 32);
+
+        let config = TestConfig::builder().formats(vec![SymbolFormat::DataMatrix]).build();
+        let result = validate_image(&DynamicImage::ImageLuma8(padded), &config).unwrap();
+        assert!(result.metadata.is_none());
+        for test in scorer::QR_ONLY_TESTS {
+            assert!(!result.stress_results.tests.contains_key(test), "{} ran", test);
+        }
+        assert!(result.stress_results.tests.contains_key("blur_light"));
+    }
+
+    #[test]
+    fn validate_rejects_non_decreasing_grades() {
+        let qr_bytes = create_test_qr();
+        let config = TestConfig {
+            grades: GradeThresholds { a: 50, b: 60, c: 40, d: 20 },
+            ..TestConfig::default()
+        };
+        let result = validate(&qr_bytes, &config);
+        assert!(matches!(result.unwrap_err(), QrScoreError::InvalidConfig(_)));
+    }
+
     #[test]
     fn validate_garbage_returns_error() {
         let config = TestConfig::default();
@@ -122,6 +624,29 @@ mod tests {
         assert_eq!(result.unwrap().content, "https://example.com");
     }
 
+    #[test]
+    fn from_reader_matches_slice_api() {
+        let qr_bytes = create_test_qr();
+        let config = TestConfig::default();
+        let result = validate_from_reader(std::io::Cursor::new(&qr_bytes), &config).unwrap();
+        assert_eq!(result.score, validate(&qr_bytes, &config).unwrap().score);
+
+        let decoded = decode_from_reader(qr_bytes.as_slice()).unwrap();
+        assert_eq!(decoded.content, "https://example.com");
+    }
+
+    #[test]
+    fn from_reader_read_failure_is_image_load() {
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("connection reset"))
+            }
+        }
+        let err = decode_from_reader(Failing).unwrap_err();
+        assert!(matches!(err, QrScoreError::ImageLoad(ref msg) if msg.contains("connection reset")));
+    }
+
     #[test]
     fn validate_score_is_reasonable() {
         let qr_bytes = create_test_qr();
@@ -141,5 +666,6 @@ mod tests {
         let result = validate(&qr_bytes, &config).unwrap();
         let meta = result.metadata.unwrap();
         let _ = meta.error_correction;
+        assert_eq!(meta.version, Some(2));
     }
 }
@@ -0,0 +1,180 @@
+use crate::scorer::{stress_test_names, weight_key_mismatches};
+use crate::types::{ConfigIssue, IssueSeverity, TestConfig, Weights};
+
+/// How far the total weight may stray from the default total before
+/// [`lint_config`] warns. Scores are normalized by the total, so this is about
+/// intent rather than arithmetic: a profile summing to half or twice the
+/// default usually has a table pasted twice or half missing.
+const WEIGHT_SUM_TOLERANCE: u32 = 10;
+
+/// Check `config` for common mistakes without scoring anything.
+///
+/// Errors cover everything [`crate::validate`] rejects (unknown weight keys,
+/// empty `formats` or `decoders`, invalid grade thresholds) plus configs that
+/// would score nonsense: zero total weight and negative or NaN blur sigmas.
+/// Warnings flag likely slips that still score: tests without a weight,
+/// weights far from the default total, and strict variants gentler than their normal
+/// counterparts. An empty result means the config is clean.
+pub fn lint_config(config: &TestConfig) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let mut error = |field: &str, message: String| {
+        issues.push(ConfigIssue { severity: IssueSeverity::Error, field: field.into(), message })
+    };
+
+    let (unknown, missing) = weight_key_mismatches(config);
+    for key in unknown {
+        error(&format!("weights.{}", key), format!("no stress test named `{}`", key));
+    }
+    if config.formats.is_empty() {
+        error("formats", "no symbologies listed, so nothing can decode".into());
+    }
+    if config.decoders.is_empty() {
+        error("decoders", "no decoders listed, so nothing can decode".into());
+    }
+    if let Err(e) = config.grades.validate() {
+        error("grades", e.to_string());
+    }
+    for (field, sigma) in [
+        ("blur_light_sigma", config.blur_light_sigma),
+        ("blur_heavy_sigma", config.blur_heavy_sigma),
+        ("scan_blur_per_factor", config.scan_blur_per_factor),
+    ] {
+        if sigma.is_nan() || sigma < 0.0 {
+            error(field, format!("must be a non-negative number, got {}", sigma));
+        }
+    }
+
+    let total_of = |weights: &Weights| {
+        stress_test_names(config).iter().filter_map(|name| weights.weight_of(name)).sum::<u32>()
+            + weights.contrast_ratio
+            + weights.edge_sharpness
+    };
+    let total = total_of(&config.weights);
+    if total == 0 {
+        error("weights", "total weight is 0, so every input scores 0".into());
+    }
+
+    let mut warning = |field: &str, message: String| {
+        issues.push(ConfigIssue { severity: IssueSeverity::Warning, field: field.into(), message })
+    };
+    for key in missing {
+        warning(
+            &format!("weights.{}", key),
+            format!("stress test `{}` has no weight, so it doesn't count towards the score", key),
+        );
+    }
+    let expected = total_of(&Weights::default());
+    if total != 0 && total.abs_diff(expected) > WEIGHT_SUM_TOLERANCE {
+        warning("weights", format!("weights sum to {}, expected about {}", total, expected));
+    }
+
+    let pairs = [
+        ("blur_heavy_sigma", config.blur_heavy_sigma, "blur_light_sigma", config.blur_light_sigma),
+        ("contrast_strict", config.contrast_strict, "contrast", config.contrast),
+        ("luminance_strict", config.luminance_strict as f32, "luminance", config.luminance as f32),
+        ("gamma_strict", config.gamma_strict, "gamma", config.gamma),
+        ("hue_strict", config.hue_strict, "hue", config.hue),
+        ("saturation_strict", config.saturation_strict, "saturation", config.saturation),
+    ];
+    for (strict_field, strict, normal_field, normal) in pairs {
+        if strict < normal {
+            warning(
+                strict_field,
+                format!("{} ({}) is gentler than {} ({})", strict_field, strict, normal_field, normal),
+            );
+        }
+    }
+    if config.scan_far_size > config.scan_mid_size {
+        warning(
+            "scan_far_size",
+            format!(
+                "scan_far_size ({}) is larger than scan_mid_size ({}), so scan_far is the closer scan",
+                config.scan_far_size, config.scan_mid_size
+            ),
+        );
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::GradeThresholds;
+
+    fn fields(issues: &[ConfigIssue], severity: IssueSeverity) -> Vec<&str> {
+        issues.iter().filter(|i| i.severity == severity).map(|i| i.field.as_str()).collect()
+    }
+
+    #[test]
+    fn default_config_is_clean() {
+        assert_eq!(lint_config(&TestConfig::default()), vec![]);
+    }
+
+    #[test]
+    fn unknown_weights_are_errors_and_missing_ones_warnings() {
+        let mut config = TestConfig::default();
+        let w = config.weights.tests.remove("blur_light").unwrap();
+        config.weights.tests.insert("blur_lite".into(), w);
+        config.weights.tests.insert("blurr_heavy".into(), 1);
+        config.weights.tests.remove("scan_far");
+
+        let issues = lint_config(&config);
+        assert_eq!(fields(&issues, IssueSeverity::Error), ["weights.blur_lite", "weights.blurr_heavy"]);
+        // Dropping scan_far's 5 leaves the sum within tolerance, so only the
+        // missing weights warn
+        assert_eq!(fields(&issues, IssueSeverity::Warning), ["weights.blur_light", "weights.scan_far"]);
+    }
+
+    #[test]
+    fn zero_total_weight_is_an_error_not_a_sum_warning() {
+        let mut config = TestConfig::default();
+        config.weights.tests.values_mut().for_each(|w| *w = 0);
+        config.weights.contrast_ratio = 0;
+        let issues = lint_config(&config);
+        assert_eq!(fields(&issues, IssueSeverity::Error), ["weights"]);
+        assert!(fields(&issues, IssueSeverity::Warning).is_empty());
+    }
+
+    #[test]
+    fn warns_when_weights_stray_from_default_total() {
+        let config = TestConfig::builder().contrast_ratio_weight(150).build();
+        let issues = lint_config(&config);
+        assert_eq!(fields(&issues, IssueSeverity::Warning), ["weights"]);
+        assert_eq!(issues[0].message, "weights sum to 204, expected about 124");
+
+        // Within tolerance
+        assert!(lint_config(&TestConfig::builder().contrast_ratio_weight(60).build()).is_empty());
+    }
+
+    #[test]
+    fn rejects_negative_and_nan_sigmas() {
+        let config = TestConfig::builder().blur_light_sigma(-1.0).scan_blur_per_factor(f32::NAN).build();
+        let errors: Vec<String> = lint_config(&config)
+            .into_iter()
+            .filter(ConfigIssue::is_error)
+            .map(|i| i.field)
+            .collect();
+        assert_eq!(errors, ["blur_light_sigma", "scan_blur_per_factor"]);
+    }
+
+    #[test]
+    fn warns_when_strict_is_gentler() {
+        let config = TestConfig::builder().contrast_strict(20.0).luminance_strict(10).build();
+        let issues = lint_config(&config);
+        assert_eq!(fields(&issues, IssueSeverity::Warning), ["contrast_strict", "luminance_strict"]);
+        assert!(issues[0].message.contains("contrast (30)"));
+    }
+
+    #[test]
+    fn includes_validate_errors() {
+        let config = TestConfig {
+            grades: GradeThresholds { a: 50, b: 60, c: 40, d: 20 },
+            ..TestConfig::default()
+        };
+        assert_eq!(fields(&lint_config(&config), IssueSeverity::Error), ["grades"]);
+
+        let config = TestConfig::builder().formats(Vec::new()).decoders(Vec::new()).build();
+        assert_eq!(fields(&lint_config(&config), IssueSeverity::Error), ["formats", "decoders"]);
+    }
+}
@@ -2,7 +2,6 @@ use std::io::{self, Read, Write};
 use std::process;
 
 use clap::Parser;
-use serde::Serialize;
 
 #[derive(Parser)]
 #[command(name = "qr-score", about = "Measure QR code scannability from SVG")]
@@ -30,35 +29,34 @@ struct Cli {
     /// Dump rendered PNG to this path instead of scoring
     #[arg(long)]
     dump_png: Option<String>,
-}
 
-#[derive(Serialize)]
-struct Output {
-    score: u8,
-    grade: String,
-    decodable: bool,
-    content: Option<String>,
-    results: qr_score::StressResults,
-    contrast_ratio: u8,
-    error_correction: Option<String>,
-}
+    /// Also write a PNG contact sheet of every stress variant, marked pass/fail, to this path
+    #[arg(long)]
+    explain: Option<String>,
+
+    /// Score against this baseline SVG instead, reporting per-test regressions; exits 1 on any regression
+    #[arg(long)]
+    compare: Option<String>,
+
+    /// Print render, decode and stress-test timings to stderr
+    #[arg(long)]
+    timings: bool,
 
-#[derive(Serialize)]
-struct ErrorOutput {
-    score: u8,
-    grade: String,
-    decodable: bool,
-    error: String,
+    /// Check the config for mistakes and print the issues instead of scoring; exits 1 on any error
+    #[arg(long)]
+    lint_config: bool,
 }
 
 fn error_json(error: &str) -> String {
-    serde_json::to_string(&ErrorOutput {
-        score: 0,
-        grade: "F".to_string(),
-        decodable: false,
-        error: error.to_string(),
-    })
-    .unwrap()
+    serde_json::to_string(&qr_score::ErrorReport::new(error)).unwrap()
+}
+
+fn write_explain(scored: &qr_score::render::ScoredSvg, path: &str) {
+    let report = qr_score::report::render_report(&scored.image, &scored.result.stress_results, &scored.config);
+    match std::fs::write(path, &report) {
+        Ok(()) => eprintln!("Wrote explain report to {}", path),
+        Err(e) => eprintln!("Failed to write explain report: {}", e),
+    }
 }
 
 fn main() {
@@ -66,6 +64,23 @@ fn main() {
 
     std::panic::set_hook(Box::new(|_| {}));
 
+    if cli.lint_config {
+        let config = match cli.config_path.as_deref().map(qr_score::render::read_config) {
+            None => qr_score::TestConfig::default(),
+            Some(Ok(config)) => config,
+            Some(Err(e)) => {
+                println!("{}", error_json(&e.to_string()));
+                process::exit(1);
+            }
+        };
+        let issues = qr_score::lint_config(&config);
+        println!("{}", serde_json::to_string(&issues).unwrap());
+        if issues.iter().any(qr_score::ConfigIssue::is_error) {
+            process::exit(1);
+        }
+        return;
+    }
+
     let mut svg_data = Vec::new();
     if let Err(e) = io::stdin().read_to_end(&mut svg_data) {
         println!("{}", error_json(&format!("Failed to read stdin: {}", e)));
@@ -90,17 +105,8 @@ fn main() {
     }
 
     if let Some(ref dump_path) = cli.dump_png {
-        let tree = usvg::Tree::from_data(&svg_data, &usvg::Options::default())
-            .unwrap_or_else(|e| { eprintln!("Invalid SVG: {}", e); process::exit(1) });
-        let config = qr_score::render::load_config(cli.config_path.as_deref(), cli.render_size);
-        let svg_size = tree.size();
-        let native = svg_size.width().max(svg_size.height()) as u32;
-        let render_size = config.render_size.max(native);
-
-        let Some(png) = qr_score::render::svg_to_png(&tree, render_size) else {
-            eprintln!("Failed to render SVG");
-            process::exit(1);
-        };
+        let (_, png) = qr_score::render::render_for_scoring(&svg_data, cli.config_path.as_deref(), cli.render_size)
+            .unwrap_or_else(|e| { eprintln!("{}", e); process::exit(1) });
         if let Err(e) = std::fs::write(dump_path, &png) {
             eprintln!("Failed to write PNG: {}", e);
             process::exit(1);
@@ -109,19 +115,57 @@ fn main() {
         return;
     }
 
-    match qr_score::render::score_svg_bytes(&svg_data, cli.config_path.as_deref(), cli.render_size) {
-        Ok(result) => {
-            let sr = &result.stress_results;
-            let output = Output {
-                score: result.score,
-                grade: qr_score::scorer::grade_from_score(result.score).to_string(),
-                decodable: result.decodable,
-                content: result.content,
-                contrast_ratio: (sr.contrast_ratio * 100.0).round() as u8,
-                results: sr.clone(),
-                error_correction: result.metadata.map(|m| m.error_correction.to_string()),
-            };
-            println!("{}", serde_json::to_string(&output).unwrap());
+    let mut config = qr_score::render::load_config(cli.config_path.as_deref(), cli.render_size);
+    config.collect_diagnostics |= cli.timings;
+
+    if let Some(ref baseline_path) = cli.compare {
+        let baseline_svg = std::fs::read(baseline_path).unwrap_or_else(|e| {
+            println!("{}", error_json(&format!("Failed to read baseline {}: {}", baseline_path, e)));
+            process::exit(1)
+        });
+        let baseline = qr_score::render::score_svg_with_config(&baseline_svg, config.clone()).ok();
+        let candidate = qr_score::render::score_svg_with_config(&svg_data, config).ok();
+        let diff = qr_score::CompareResult::between(baseline.as_ref(), candidate.as_ref());
+        println!("{}", serde_json::to_string(&diff).unwrap());
+        if diff.has_regressions() {
+            process::exit(1);
+        }
+        return;
+    }
+
+    // --explain draws the image that was actually scored, so keep it around
+    let scored = match cli.explain {
+        Some(_) => qr_score::render::score_svg_with_image(&svg_data, config)
+            .map(|scored| (scored.result.clone(), Some(scored))),
+        None => qr_score::render::score_svg_with_config(&svg_data, config).map(|result| (result, None)),
+    };
+
+    match scored {
+        Ok((result, scored)) => {
+            if let Some(d) = result.diagnostics.filter(|_| cli.timings) {
+                eprintln!(
+                    "render: {:.1}ms  decode: {:.1}ms  stress: {:.1}ms",
+                    d.render_ms.unwrap_or(0.0),
+                    d.decode_ms,
+                    d.stress_ms
+                );
+                if let Some(info) = d.render.filter(|info| info.overridden()) {
+                    eprintln!(
+                        "rendered at {}px: SVG native size {}px overrides render_size {}px",
+                        info.effective, info.native, info.configured
+                    );
+                }
+                if d.upscale_retry {
+                    eprintln!("first render didn't decode; scored the upscale_retry render");
+                }
+            }
+
+            let report = qr_score::ScoreReport::from(result);
+            println!("{}", serde_json::to_string(&report).unwrap());
+
+            if let (Some(explain_path), Some(scored)) = (&cli.explain, &scored) {
+                write_explain(scored, explain_path);
+            }
         }
         Err(e) => {
             println!("{}", error_json(&e.to_string()));
@@ -19,8 +19,10 @@ pub fn svg_to_png(tree: &usvg::Tree, size: u32) -> Option<Vec<u8>> {
 
 /// High-quality render with DPI and zoom (used for `--render` CLI mode).
 pub fn svg_to_png_hq(svg_data: &[u8], dpi: f32, zoom: f32) -> Option<Vec<u8>> {
-    let mut opts = usvg::Options::default();
-    opts.dpi = dpi;
+    let opts = usvg::Options {
+        dpi,
+        ..usvg::Options::default()
+    };
     let tree = usvg::Tree::from_data(svg_data, &opts).ok()?;
 
     let svg_size = tree.size();
@@ -37,22 +39,21 @@ pub fn svg_to_png_hq(svg_data: &[u8], dpi: f32, zoom: f32) -> Option<Vec<u8>> {
     pixmap.encode_png().ok()
 }
 
+/// Read and parse the TOML config at `path`. Unlike [`load_config`], a file
+/// that can't be read or parsed is an error rather than the defaults.
+pub fn read_config(path: &str) -> crate::Result<crate::TestConfig> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| crate::QrScoreError::InvalidConfig(format!("failed to read {}: {}", path, e)))?;
+    toml::from_str(&contents).map_err(|e| crate::QrScoreError::InvalidConfig(format!("failed to parse {}: {}", path, e)))
+}
+
 pub fn load_config(config_path: Option<&str>, render_size_override: Option<u32>) -> crate::TestConfig {
-    let mut config = 'load: {
-        let Some(path) = config_path else { break 'load crate::TestConfig::default() };
-        let contents = match std::fs::read_to_string(path) {
-            Ok(c) => c,
-            Err(e) => {
-                eprintln!("Warning: failed to read config {}: {}", path, e);
-                break 'load crate::TestConfig::default();
-            }
-        };
-        match toml::from_str(&contents) {
-            Ok(c) => c,
-            Err(e) => {
-                eprintln!("Warning: failed to parse config {}: {}", path, e);
-                crate::TestConfig::default()
-            }
+    let mut config = match config_path.map(read_config) {
+        None => crate::TestConfig::default(),
+        Some(Ok(config)) => config,
+        Some(Err(e)) => {
+            eprintln!("Warning: {}", e);
+            crate::TestConfig::default()
         }
     };
 
@@ -60,23 +61,354 @@ pub fn load_config(config_path: Option<&str>, render_size_override: Option<u32>)
     config
 }
 
+/// SVG bytes -> parse -> render, returning the config to score with (with
+/// `native_size` filled in from the SVG) and the rendered PNG.
+pub fn render_for_scoring(
+    svg_data: &[u8],
+    config_path: Option<&str>,
+    render_size_override: Option<u32>,
+) -> crate::Result<(crate::TestConfig, Vec<u8>)> {
+    render_with_config(svg_data, load_config(config_path, render_size_override))
+}
+
+/// [`render_for_scoring`] with an already-loaded config.
+pub fn render_with_config(
+    svg_data: &[u8],
+    mut config: crate::TestConfig,
+) -> crate::Result<(crate::TestConfig, Vec<u8>)> {
+    let (png_bytes, info) = render_svg_with_info(svg_data, &config)?;
+    config.native_size = Some(info.native);
+    Ok((config, png_bytes))
+}
+
+/// Render an SVG for scoring and report the size it was rendered at, which
+/// is `config.render_size` or the SVG's native size, whichever is larger.
+pub fn render_svg_with_info(
+    svg_data: &[u8],
+    config: &crate::TestConfig,
+) -> crate::Result<(Vec<u8>, crate::RenderInfo)> {
+    render_tree(&parse_svg(svg_data)?, config)
+}
+
+fn parse_svg(svg_data: &[u8]) -> crate::Result<usvg::Tree> {
+    usvg::Tree::from_data(svg_data, &usvg::Options::default())
+        .map_err(|e| crate::QrScoreError::InvalidSvg(e.to_string()))
+}
+
+fn render_tree(tree: &usvg::Tree, config: &crate::TestConfig) -> crate::Result<(Vec<u8>, crate::RenderInfo)> {
+    let svg_size = tree.size();
+    let native = svg_size.width().max(svg_size.height()) as u32;
+    let info = crate::RenderInfo {
+        configured: config.render_size,
+        native,
+        effective: config.render_size.max(native),
+    };
+
+    let png_bytes = svg_to_png(tree, info.effective).ok_or(crate::QrScoreError::RenderFailed)?;
+    Ok((png_bytes, info))
+}
+
+/// Report what in an SVG isn't plain vector paths: embedded bitmaps, text
+/// and references to outside files.
+pub fn inspect_svg(svg_data: &[u8]) -> crate::Result<crate::SvgDiagnostics> {
+    Ok(inspect_tree(svg_data, &parse_svg(svg_data)?))
+}
+
+/// Raster images are found in the parsed tree, where usvg has resolved them.
+/// Text and external references are found in the source, since usvg drops
+/// text it has no font for and references it can't load.
+fn inspect_tree(svg_data: &[u8], tree: &usvg::Tree) -> crate::SvgDiagnostics {
+    let mut diagnostics = crate::SvgDiagnostics {
+        raster_image: group_has_raster(tree.root()),
+        ..Default::default()
+    };
+
+    let source = if svg_data.starts_with(&[0x1f, 0x8b]) {
+        usvg::decompress_svgz(svg_data).ok()
+    } else {
+        Some(svg_data.to_vec())
+    };
+    let Some(text) = source.as_deref().and_then(|s| std::str::from_utf8(s).ok()) else {
+        return diagnostics;
+    };
+    let options = usvg::roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() };
+    let Ok(doc) = usvg::roxmltree::Document::parse_with_options(text, options) else {
+        return diagnostics;
+    };
+
+    for node in doc.descendants().filter(|n| n.is_element()) {
+        diagnostics.text |= node.tag_name().name() == "text";
+        diagnostics.external_reference |= node
+            .attributes()
+            .filter(|a| a.name() == "href")
+            .any(|a| !a.value().starts_with('#') && !a.value().starts_with("data:"));
+    }
+    diagnostics
+}
+
+fn group_has_raster(group: &usvg::Group) -> bool {
+    group.children().iter().any(|node| {
+        match node {
+            usvg::Node::Image(image) if !matches!(image.kind(), usvg::ImageKind::SVG(_)) => return true,
+            usvg::Node::Group(child) if group_has_raster(child) => return true,
+            _ => {}
+        }
+        // Clip paths, masks, patterns and nested SVG images
+        let mut nested = false;
+        node.subroots(|root| nested |= group_has_raster(root));
+        nested
+    })
+}
+
 /// Full pipeline: SVG bytes -> parse -> render -> validate.
 pub fn score_svg_bytes(
     svg_data: &[u8],
     config_path: Option<&str>,
     render_size_override: Option<u32>,
 ) -> crate::Result<crate::ValidationResult> {
-    let tree = usvg::Tree::from_data(svg_data, &usvg::Options::default())
-        .map_err(|e| crate::QrScoreError::InvalidSvg(e.to_string()))?;
+    score_svg_with_config(svg_data, load_config(config_path, render_size_override))
+}
 
-    let mut config = load_config(config_path, render_size_override);
-    let svg_size = tree.size();
-    let native = svg_size.width().max(svg_size.height()) as u32;
-    config.native_size = Some(native);
-    let render_size = config.render_size.max(native);
+/// [`score_svg_bytes`] with an already-loaded config. Fills in the render
+/// timing and [`crate::RenderInfo`] when `collect_diagnostics` is set. A
+/// `timeout` bounds rendering and scoring together.
+///
+/// With `upscale_retry`, an SVG that doesn't decode is rendered once more at
+/// twice the size and scored again. If that fails too, the first error is
+/// returned.
+pub fn score_svg_with_config(
+    svg_data: &[u8],
+    config: crate::TestConfig,
+) -> crate::Result<crate::ValidationResult> {
+    score_svg_timed(svg_data, config).map(|(result, _, _)| result)
+}
+
+/// A scored SVG along with the image the stress tests actually ran on and
+/// the config they ran with, so reports drawn from it match the results.
+#[derive(Debug, Clone)]
+pub struct ScoredSvg {
+    pub result: crate::ValidationResult,
+    /// The render that was scored (the `upscale_retry` one if it was used),
+    /// with `crop` applied.
+    pub image: image::DynamicImage,
+    /// The config that render was scored with, `native_size` and any retry
+    /// `render_size` included.
+    pub config: crate::TestConfig,
+}
+
+/// [`score_svg_with_config`], also returning what was scored. For
+/// [`crate::report::render_report`].
+pub fn score_svg_with_image(svg_data: &[u8], config: crate::TestConfig) -> crate::Result<ScoredSvg> {
+    let (result, png_bytes, config) = score_svg_timed(svg_data, config)?;
+    let mut image = crate::decoder::load_image(&png_bytes, config.respect_exif)?;
+    if let Some(rect) = config.crop {
+        image = crate::crop_image(&image, rect)?;
+    }
+    Ok(ScoredSvg { result, image, config })
+}
+
+/// Score under `config.timeout`, returning the result with the PNG and
+/// config that produced it.
+fn score_svg_timed(
+    svg_data: &[u8],
+    config: crate::TestConfig,
+) -> crate::Result<(crate::ValidationResult, Vec<u8>, crate::TestConfig)> {
+    let Some(timeout) = config.timeout else {
+        return score_svg_until(svg_data, config, None);
+    };
+    let deadline = std::time::Instant::now() + timeout;
+    let svg_data = svg_data.to_vec();
+    crate::with_deadline(deadline, move || score_svg_until(&svg_data, config, Some(deadline)))
+}
+
+fn score_svg_until(
+    svg_data: &[u8],
+    mut config: crate::TestConfig,
+    deadline: Option<std::time::Instant>,
+) -> crate::Result<(crate::ValidationResult, Vec<u8>, crate::TestConfig)> {
+    let render_start = std::time::Instant::now();
+    let tree = parse_svg(svg_data)?;
+    crate::check_deadline(deadline)?;
+    let (png_bytes, mut info) = render_tree(&tree, &config)?;
+    let mut render_time = render_start.elapsed();
+    config.native_size = Some(info.native);
 
-    let png_bytes = svg_to_png(&tree, render_size).ok_or(crate::QrScoreError::RenderFailed)?;
+    let mut upscaled = false;
+    let (mut result, png_bytes, config) = match validate_png(&png_bytes, &config, deadline) {
+        Err(e) if config.upscale_retry && is_decode_failure(&e) => {
+            let retry_config = crate::TestConfig {
+                render_size: info.effective.saturating_mul(2),
+                ..config.clone()
+            };
+            crate::check_deadline(deadline)?;
+            let retry_start = std::time::Instant::now();
+            let (retry_png, retry_info) = render_tree(&tree, &retry_config)?;
+            render_time += retry_start.elapsed();
+            match validate_png(&retry_png, &retry_config, deadline) {
+                Ok(result) => {
+                    info = retry_info;
+                    upscaled = true;
+                    Ok((result, retry_png, retry_config))
+                }
+                Err(crate::QrScoreError::TimedOut) => Err(crate::QrScoreError::TimedOut),
+                Err(_) => Err(e),
+            }
+        }
+        other => other.map(|result| (result, png_bytes, config)),
+    }?;
+    result.svg = Some(inspect_tree(svg_data, &tree));
+    if let Some(diagnostics) = result.diagnostics.as_mut() {
+        diagnostics.render_ms = Some(crate::millis(render_time));
+        diagnostics.render = Some(info);
+        diagnostics.upscale_retry = upscaled;
+    }
+    Ok((result, png_bytes, config))
+}
 
-    panic::catch_unwind(|| crate::validate(&png_bytes, &config))
+fn validate_png(
+    png_bytes: &[u8],
+    config: &crate::TestConfig,
+    deadline: Option<std::time::Instant>,
+) -> crate::Result<crate::ValidationResult> {
+    panic::catch_unwind(|| crate::validate_until(png_bytes, config, deadline))
         .map_err(|_| crate::QrScoreError::DecodeFailed)?
-}
\ No newline at end of file
+}
+
+/// Errors a larger render might fix, as opposed to bad config or a timeout.
+fn is_decode_failure(error: &crate::QrScoreError) -> bool {
+    matches!(
+        error,
+        crate::QrScoreError::DecodeFailed
+            | crate::QrScoreError::UnreadableSymbol
+            | crate::QrScoreError::DecoderPanicked
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qr_svg(size: u32) -> Vec<u8> {
+        let code = qrcode::QrCode::new(b"https://example.com").unwrap();
+        code.render::<qrcode::render::svg::Color>()
+            .min_dimensions(size, size)
+            .max_dimensions(size, size)
+            .build()
+            .into_bytes()
+    }
+
+    #[test]
+    fn read_config_reports_parse_errors_load_config_hides() {
+        let path = std::env::temp_dir().join(format!("qr-score-read-config-{}.toml", std::process::id()));
+        std::fs::write(&path, "contrast = \"high\"").unwrap();
+        let path = path.to_str().unwrap();
+
+        assert!(matches!(read_config(path), Err(crate::QrScoreError::InvalidConfig(_))));
+        assert_eq!(load_config(Some(path), None).contrast, crate::TestConfig::default().contrast);
+        std::fs::remove_file(path).unwrap();
+
+        assert!(matches!(read_config(path), Err(crate::QrScoreError::InvalidConfig(_))));
+    }
+
+    #[test]
+    fn render_info_keeps_configured_size_for_small_svg() {
+        let config = crate::TestConfig::default();
+        let (png, info) = render_svg_with_info(&qr_svg(200), &config).unwrap();
+        assert_eq!(info.configured, 400);
+        assert!(info.native <= 200);
+        assert_eq!(info.effective, 400);
+        assert!(!info.overridden());
+        assert_eq!(image::load_from_memory(&png).unwrap().width(), 400);
+    }
+
+    #[test]
+    fn render_info_reports_native_override() {
+        let config = crate::TestConfig::default();
+        let (_, info) = render_svg_with_info(&qr_svg(1000), &config).unwrap();
+        assert!(info.native > 400);
+        assert_eq!(info.effective, info.native);
+        assert!(info.overridden());
+    }
+
+    #[test]
+    fn score_svg_times_out() {
+        let config = crate::TestConfig::builder().timeout(std::time::Duration::ZERO).build();
+        let err = score_svg_with_config(&qr_svg(200), config).unwrap_err();
+        assert!(matches!(err, crate::QrScoreError::TimedOut));
+    }
+
+    #[test]
+    fn score_svg_diagnostics_include_render_info() {
+        let config = crate::TestConfig::builder().collect_diagnostics(true).build();
+        let result = score_svg_with_config(&qr_svg(200), config).unwrap();
+        let diagnostics = result.diagnostics.unwrap();
+        assert!(diagnostics.render_ms.is_some());
+        assert_eq!(diagnostics.render.map(|r| r.effective), Some(400));
+        assert!(!diagnostics.upscale_retry);
+    }
+
+    #[test]
+    fn upscale_retry_recovers_undersized_render() {
+        // The renderer clamps to at least a pixel per module, so shrink the
+        // declared size by hand; the viewBox keeps the drawing intact
+        let code = qrcode::QrCode::new(b"https://example.com").unwrap();
+        let svg = code.render::<qrcode::render::svg::Color>().build()
+            .replacen(r#"width="264" height="264""#, r#"width="24" height="24""#, 1)
+            .into_bytes();
+        let config = crate::TestConfig::builder()
+            .render_size(24)
+            .collect_diagnostics(true)
+            .build();
+        assert!(score_svg_with_config(&svg, config.clone()).is_err());
+
+        let retry = crate::TestConfig { upscale_retry: true, ..config };
+        let result = score_svg_with_config(&svg, retry.clone()).unwrap();
+        let diagnostics = result.diagnostics.unwrap();
+        assert!(diagnostics.upscale_retry);
+        assert_eq!(diagnostics.render.map(|r| r.effective), Some(48));
+
+        // The image handed back is the retry render, scored at the retry size
+        let scored = score_svg_with_image(&svg, retry).unwrap();
+        assert_eq!(scored.image.width(), 48);
+        assert_eq!(scored.config.render_size, 48);
+    }
+
+    #[test]
+    fn score_svg_with_image_returns_the_cropped_render() {
+        let config = crate::TestConfig::builder().crop(24, 24, 352, 352).build();
+        let scored = score_svg_with_image(&qr_svg(400), config).unwrap();
+        assert_eq!((scored.image.width(), scored.image.height()), (352, 352));
+        assert!(scored.config.native_size.is_some());
+        assert_eq!(scored.result.score, score_svg_with_config(&qr_svg(400), scored.config.clone()).unwrap().score);
+    }
+
+    #[test]
+    fn inspect_svg_plain_qr_is_pure_vector() {
+        let diagnostics = inspect_svg(&qr_svg(200)).unwrap();
+        assert!(diagnostics.is_pure_vector(), "{:?}", diagnostics);
+        let result = score_svg_with_config(&qr_svg(200), crate::TestConfig::default()).unwrap();
+        assert_eq!(result.svg, Some(diagnostics));
+    }
+
+    #[test]
+    fn inspect_svg_flags_raster_text_and_external_refs() {
+        // A 1x1 PNG
+        let data = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==";
+        let svg = |body: &str| {
+            format!(r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="40" height="40">{}</svg>"#, body)
+        };
+
+        let embedded = svg(&format!(r#"<image width="40" height="40" href="data:image/png;base64,{}"/>"#, data));
+        let d = inspect_svg(embedded.as_bytes()).unwrap();
+        assert!(d.raster_image && !d.text && !d.external_reference, "{:?}", d);
+
+        let text = svg(r#"<text x="0" y="20">QR</text>"#);
+        let d = inspect_svg(text.as_bytes()).unwrap();
+        assert!(d.text && !d.raster_image, "{:?}", d);
+
+        let linked = svg(r##"<defs><rect id="r" width="4" height="4"/></defs><use xlink:href="#r"/><image width="40" height="40" xlink:href="logo.png"/>"##);
+        let d = inspect_svg(linked.as_bytes()).unwrap();
+        assert!(d.external_reference && !d.raster_image, "{:?}", d);
+        assert!(!d.is_pure_vector());
+    }
+}
@@ -0,0 +1,191 @@
+use crate::scorer::stress_variants;
+use crate::types::{StressResults, TestConfig};
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, Rgb, RgbImage};
+
+const THUMB: u32 = 128;
+const BORDER: u32 = 6;
+const LABEL_HEIGHT: u32 = 12;
+const GAP: u32 = 4;
+const COLUMNS: u32 = 6;
+
+const PASS: Rgb<u8> = Rgb([40, 170, 70]);
+const FAIL: Rgb<u8> = Rgb([210, 40, 40]);
+const BACKGROUND: Rgb<u8> = Rgb([238, 238, 238]);
+const TEXT: Rgb<u8> = Rgb([0, 0, 0]);
+
+/// Render a PNG contact sheet of every stress variant, each bordered green
+/// (passed) or red (failed) and labelled with its test name.
+///
+/// `config` must be the one the results were scored with, since it decides
+/// which variants exist and how strong each transform is. Tests that aren't
+/// an image transform (such as `quiet_zone`) are shown on the original image.
+pub fn render_report(img: &DynamicImage, results: &StressResults, config: &TestConfig) -> Vec<u8> {
+    let mut cells = stress_variants(img, config);
+    for name in results.tests.keys() {
+        if !cells.iter().any(|(n, _)| n == name) {
+            cells.push((name.clone(), img.clone()));
+        }
+    }
+    cells.retain(|(name, _)| results.tests.contains_key(name));
+
+    let cell_w = THUMB + 2 * BORDER;
+    let cell_h = cell_w + LABEL_HEIGHT;
+    let count = cells.len() as u32;
+    let cols = count.clamp(1, COLUMNS);
+    let rows = count.div_ceil(COLUMNS).max(1);
+    let mut sheet = RgbImage::from_pixel(
+        GAP + cols * (cell_w + GAP),
+        GAP + rows * (cell_h + GAP),
+        BACKGROUND,
+    );
+
+    for (i, (name, variant)) in cells.iter().enumerate() {
+        let x = GAP + (i as u32 % COLUMNS) * (cell_w + GAP);
+        let y = GAP + (i as u32 / COLUMNS) * (cell_h + GAP);
+        let color = if results.tests[name].passed() { PASS } else { FAIL };
+
+        fill_rect(&mut sheet, x, y, cell_w, cell_w, color);
+        fill_rect(&mut sheet, x + BORDER, y + BORDER, THUMB, THUMB, Rgb([255, 255, 255]));
+        let thumb = thumbnail(variant);
+        let ox = x + BORDER + (THUMB - thumb.width()) / 2;
+        let oy = y + BORDER + (THUMB - thumb.height()) / 2;
+        image::imageops::overlay(&mut sheet, &thumb, ox as i64, oy as i64);
+
+        draw_text(&mut sheet, x, y + cell_w + 3, name);
+    }
+
+    let mut buf = Vec::new();
+    DynamicImage::ImageRgb8(sheet)
+        .write_to(&mut std::io::Cursor::new(&mut buf), image::ImageFormat::Png)
+        .expect("encoding an in-memory RGB image as PNG cannot fail");
+    buf
+}
+
+/// Fit into a `THUMB` square, using nearest-neighbour when enlarging so that
+/// downscaled variants keep their visible pixelation.
+fn thumbnail(img: &DynamicImage) -> RgbImage {
+    let (w, h) = img.dimensions();
+    let filter = if w.max(h) < THUMB {
+        FilterType::Nearest
+    } else {
+        FilterType::Triangle
+    };
+    img.resize(THUMB, THUMB, filter).to_rgb8()
+}
+
+fn fill_rect(img: &mut RgbImage, x: u32, y: u32, w: u32, h: u32, color: Rgb<u8>) {
+    for py in y..(y + h).min(img.height()) {
+        for px in x..(x + w).min(img.width()) {
+            img.put_pixel(px, py, color);
+        }
+    }
+}
+
+fn draw_text(img: &mut RgbImage, x: u32, y: u32, text: &str) {
+    for (i, ch) in text.chars().enumerate() {
+        let gx = x + i as u32 * 6;
+        for (row, bits) in glyph(ch).iter().enumerate() {
+            for col in 0..5 {
+                if bits & (0x10 >> col) != 0 {
+                    let (px, py) = (gx + col, y + row as u32);
+                    if px < img.width() && py < img.height() {
+                        img.put_pixel(px, py, TEXT);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 5x7 bitmap glyphs for the characters that appear in test names.
+fn glyph(ch: char) -> [u8; 7] {
+    match ch {
+        'a' => [0x00, 0x00, 0x0E, 0x01, 0x0F, 0x11, 0x0F],
+        'b' => [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x1E],
+        'c' => [0x00, 0x00, 0x0E, 0x10, 0x10, 0x11, 0x0E],
+        'd' => [0x01, 0x01, 0x0D, 0x13, 0x11, 0x11, 0x0F],
+        'e' => [0x00, 0x00, 0x0E, 0x11, 0x1F, 0x10, 0x0E],
+        'f' => [0x06, 0x09, 0x08, 0x1C, 0x08, 0x08, 0x08],
+        'g' => [0x00, 0x0F, 0x11, 0x11, 0x0F, 0x01, 0x0E],
+        'h' => [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x11],
+        'i' => [0x04, 0x00, 0x0C, 0x04, 0x04, 0x04, 0x0E],
+        'j' => [0x02, 0x00, 0x06, 0x02, 0x02, 0x12, 0x0C],
+        'k' => [0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12],
+        'l' => [0x0C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'm' => [0x00, 0x00, 0x1A, 0x15, 0x15, 0x11, 0x11],
+        'n' => [0x00, 0x00, 0x16, 0x19, 0x11, 0x11, 0x11],
+        'o' => [0x00, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x0E],
+        'p' => [0x00, 0x00, 0x1E, 0x11, 0x1E, 0x10, 0x10],
+        'q' => [0x00, 0x00, 0x0D, 0x13, 0x0F, 0x01, 0x01],
+        'r' => [0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10],
+        's' => [0x00, 0x00, 0x0E, 0x10, 0x0E, 0x01, 0x1E],
+        't' => [0x08, 0x08, 0x1C, 0x08, 0x08, 0x09, 0x06],
+        'u' => [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0D],
+        'v' => [0x00, 0x00, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'w' => [0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0A],
+        'x' => [0x00, 0x00, 0x11, 0x0A, 0x04, 0x0A, 0x11],
+        'y' => [0x00, 0x00, 0x11, 0x11, 0x0F, 0x01, 0x0E],
+        'z' => [0x00, 0x00, 0x1F, 0x02, 0x04, 0x08, 0x1F],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        _ => [0x00; 7],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TestOutcome;
+    use std::collections::BTreeMap;
+
+    fn create_test_qr_image() -> DynamicImage {
+        use image::Luma;
+        let code = qrcode::QrCode::new(b"https://example.com").unwrap();
+        DynamicImage::ImageLuma8(code.render::<Luma<u8>>().build())
+    }
+
+    #[test]
+    fn report_marks_pass_and_fail() {
+        let img = create_test_qr_image();
+        let config = TestConfig::default();
+        let tests = BTreeMap::from([
+            ("downscale_1x".to_string(), TestOutcome::Pass),
+            ("blur_heavy".to_string(), TestOutcome::NoGrid),
+            ("quiet_zone".to_string(), TestOutcome::Pass),
+        ]);
+        let results = StressResults { tests, ..StressResults::default() };
+
+        let png = render_report(&img, &results, &config);
+        let sheet = image::load_from_memory(&png).unwrap().to_rgb8();
+        let cell_w = THUMB + 2 * BORDER;
+        assert_eq!(sheet.width(), GAP + 3 * (cell_w + GAP));
+        assert_eq!(sheet.height(), GAP + cell_w + LABEL_HEIGHT + GAP);
+
+        // Cells follow variant order (downscale_1x, blur_heavy), then non-variant tests
+        assert_eq!(*sheet.get_pixel(GAP + 1, GAP + 1), PASS);
+        assert_eq!(*sheet.get_pixel(2 * GAP + cell_w + 1, GAP + 1), FAIL);
+        assert_eq!(*sheet.get_pixel(3 * GAP + 2 * cell_w + 1, GAP + 1), PASS);
+    }
+
+    #[test]
+    fn report_covers_every_scored_test() {
+        let img = create_test_qr_image();
+        let config = TestConfig::default();
+        let (results, _) = crate::scorer::validate(&img, &config);
+        let png = render_report(&img, &results, &config);
+        let sheet = image::load_from_memory(&png).unwrap();
+        let rows = (results.tests.len() as u32).div_ceil(COLUMNS);
+        assert_eq!(sheet.height(), GAP + rows * (THUMB + 2 * BORDER + LABEL_HEIGHT + GAP));
+    }
+}
@@ -1,77 +1,282 @@
 use crate::decoder::try_decode;
-use crate::types::{StressResults, TestConfig};
+use crate::error::{QrScoreError, Result};
+use crate::geometry::{check_finder_patterns_at, locate_grid, measure_quiet_zone_at, GridLocation};
+use crate::types::{GradeThresholds, IsoGrade, Polarity, StressResults, SymbolFormat, TestConfig, TestOutcome};
 use image::imageops::FilterType;
-use image::{DynamicImage, GenericImageView, RgbImage};
+use image::{DynamicImage, GenericImageView, Pixel, Rgb, RgbImage};
 use rayon::prelude::*;
 use std::collections::BTreeMap;
+use std::time::Instant;
 
+/// Stress-test `img` as a QR code and score the results.
 pub fn validate(img: &DynamicImage, config: &TestConfig) -> (StressResults, u8) {
-    let stress = run_stress_tests(img, config);
+    validate_until(img, config, SymbolFormat::Qr, locate_grid(img).as_ref(), None)
+        .expect("stress tests only time out with a deadline")
+}
+
+/// [`validate`] for a symbol decoded as `format` at `location`, giving up
+/// with [`QrScoreError::TimedOut`] once `deadline` passes. Variants not yet
+/// generated or decoded by then are skipped.
+pub(crate) fn validate_until(
+    img: &DynamicImage,
+    config: &TestConfig,
+    format: SymbolFormat,
+    location: Option<&GridLocation>,
+    deadline: Option<Instant>,
+) -> Result<(StressResults, u8)> {
+    let stress = run_stress_tests(img, config, format, location, deadline)?;
     let score = calculate_score(&stress, &config.weights);
-    (stress, score)
+    Ok((stress, score))
 }
 
-fn run_stress_tests(img: &DynamicImage, config: &TestConfig) -> StressResults {
-    let contrast_ratio = measure_contrast(img);
+/// Every image-transform stress variant for `img`, keyed by test name.
+///
+/// Downscales come first: one `downscale_<N>px` per `config.downscale_sizes`
+/// entry when set, otherwise `downscale_1x`..`downscale_4x` as multiples of the
+/// native size.
+///
+/// Shared by scoring and [`crate::report::render_report`] so both see the same images.
+pub fn stress_variants(img: &DynamicImage, config: &TestConfig) -> Vec<(String, DynamicImage)> {
+    stress_variants_until(img, config, locate_grid(img).as_ref(), None).expect("variants only time out with a deadline")
+}
 
-    let native = config.native_size.unwrap_or(100);
-    let variants: Vec<(&str, DynamicImage)> = vec![
-        ("downscale_1x", resize_to(img, native)),
-        ("downscale_2x", resize_to(img, native * 2)),
-        ("downscale_3x", resize_to(img, native * 3)),
-        ("downscale_4x", resize_to(img, native * 4)),
-        ("blur_light", apply_blur(img, config.blur_light_sigma)),
-        ("blur_heavy", apply_blur(img, config.blur_heavy_sigma)),
-        ("contrast_up", adjust_contrast(img, config.contrast)),
-        ("contrast_down", adjust_contrast(img, -config.contrast)),
-        ("contrast_strict_up", adjust_contrast(img, config.contrast_strict)),
-        ("contrast_strict_down", adjust_contrast(img, -config.contrast_strict)),
-        ("luminance_up", adjust_luminance(img, config.luminance)),
-        ("luminance_down", adjust_luminance(img, -config.luminance)),
-        ("luminance_strict_up", adjust_luminance(img, config.luminance_strict)),
-        ("luminance_strict_down", adjust_luminance(img, -config.luminance_strict)),
-        ("hue_up", shift_hue(img, config.hue)),
-        ("hue_down", shift_hue(img, -config.hue)),
-        ("hue_strict_up", shift_hue(img, config.hue_strict)),
-        ("hue_strict_down", shift_hue(img, -config.hue_strict)),
-        ("saturation_up", adjust_saturation(img, config.saturation)),
-        ("saturation_down", adjust_saturation(img, -config.saturation)),
-        ("saturation_strict_up", adjust_saturation(img, config.saturation_strict)),
-        ("saturation_strict_down", adjust_saturation(img, -config.saturation_strict)),
+/// Produces one stress variant, run on demand.
+type Transform<'a> = Box<dyn Fn() -> DynamicImage + 'a>;
+
+/// [`stress_variants`] for a grid the caller already located, checking
+/// `deadline` before each transform so a timed-out run stops generating
+/// rather than finishing every image.
+pub(crate) fn stress_variants_until(
+    img: &DynamicImage,
+    config: &TestConfig,
+    location: Option<&GridLocation>,
+    deadline: Option<Instant>,
+) -> Result<Vec<(String, DynamicImage)>> {
+    // Luminance-only transforms share one luma conversion and produce luma
+    // images, which try_decode hands to the decoders without converting again.
+    // Only hue and saturation need the color image, and they recolor just
+    // the module cluster.
+    //
+    // Contrast and brightness clamp each channel and gamma bends it, so on a
+    // coloured image they don't commute with the luma conversion. Those, and
+    // the resampling ones (which round per channel), keep working on the
+    // colour image unless it is grey anyway. So only grey inputs save time:
+    // about half on those variants for a version-10 code, while colour ones
+    // cost what they did (see `luma_path_timing_on_version_10`).
+    let luma = DynamicImage::ImageLuma8(img.to_luma8());
+    let base = if is_grey(img) { &luma } else { img };
+    let modules = module_mask(img, config.polarity);
+    let colored = |variant| on_modules(img, variant, modules.as_deref());
+    let background = background_luma(&luma, image_polarity(img, config.polarity));
+
+    let mut variants: Vec<(String, Transform)> = downscale_targets(config)
+        .into_iter()
+        .map(|(name, size)| (name, Box::new(move || resize_to(base, size)) as Transform))
+        .collect();
+
+    let transforms: Vec<(&str, Transform)> = vec![
+        ("blur_light", Box::new(|| apply_blur(base, config.blur_light_sigma))),
+        ("blur_heavy", Box::new(|| apply_blur(base, config.blur_heavy_sigma))),
+        ("contrast_up", Box::new(|| adjust_contrast(base, config.contrast))),
+        ("contrast_down", Box::new(|| adjust_contrast(base, -config.contrast))),
+        ("contrast_strict_up", Box::new(|| adjust_contrast(base, config.contrast_strict))),
+        ("contrast_strict_down", Box::new(|| adjust_contrast(base, -config.contrast_strict))),
+        ("luminance_up", Box::new(|| adjust_luminance(base, config.luminance))),
+        ("luminance_down", Box::new(|| adjust_luminance(base, -config.luminance))),
+        ("luminance_strict_up", Box::new(|| adjust_luminance(base, config.luminance_strict))),
+        ("luminance_strict_down", Box::new(|| adjust_luminance(base, -config.luminance_strict))),
+        ("gamma_up", Box::new(|| apply_gamma(base, config.gamma))),
+        ("gamma_down", Box::new(|| apply_gamma(base, 1.0 / config.gamma))),
+        ("gamma_strict_up", Box::new(|| apply_gamma(base, config.gamma_strict))),
+        ("gamma_strict_down", Box::new(|| apply_gamma(base, 1.0 / config.gamma_strict))),
+        ("hue_up", Box::new(|| colored(shift_hue(img, config.hue)))),
+        ("hue_down", Box::new(|| colored(shift_hue(img, -config.hue)))),
+        ("hue_strict_up", Box::new(|| colored(shift_hue(img, config.hue_strict)))),
+        ("hue_strict_down", Box::new(|| colored(shift_hue(img, -config.hue_strict)))),
+        ("saturation_up", Box::new(|| colored(adjust_saturation(img, config.saturation)))),
+        ("saturation_down", Box::new(|| colored(adjust_saturation(img, -config.saturation)))),
+        ("saturation_strict_up", Box::new(|| colored(adjust_saturation(img, config.saturation_strict)))),
+        ("saturation_strict_down", Box::new(|| colored(adjust_saturation(img, -config.saturation_strict)))),
+        ("lighting_gradient", Box::new(|| apply_lighting_gradient(&luma, config.lighting_gradient))),
+        ("occlude_center", Box::new(|| occlude_center(&luma, location, config.occlusion_fraction, config.occlusion_color))),
+        ("erasure", Box::new(|| apply_erasure(&luma, location, config.erasure_fraction, config.erasure_seed, background))),
+        ("halftone", Box::new(|| apply_halftone(&luma, config.halftone_cell))),
+        ("scan_mid", Box::new(|| simulate_scan(base, config.scan_mid_size, config.scan_blur_per_factor))),
+        ("scan_far", Box::new(|| simulate_scan(base, config.scan_far_size, config.scan_blur_per_factor))),
     ];
 
-    let tests: BTreeMap<String, bool> = variants
+    variants.extend(transforms.into_iter().map(|(name, make)| (name.to_string(), make)));
+    variants
+        .into_iter()
+        .map(|(name, make)| {
+            crate::check_deadline(deadline)?;
+            Ok((name, make()))
+        })
+        .collect()
+}
+
+/// Whether every pixel of `img` is grey, so converting it to luma loses nothing.
+fn is_grey(img: &DynamicImage) -> bool {
+    match img {
+        DynamicImage::ImageLuma8(_)
+        | DynamicImage::ImageLumaA8(_)
+        | DynamicImage::ImageLuma16(_)
+        | DynamicImage::ImageLumaA16(_) => true,
+        _ => img.to_rgb8().pixels().all(|p| p.0[0] == p.0[1] && p.0[1] == p.0[2]),
+    }
+}
+
+/// Name and target size of each downscale test.
+fn downscale_targets(config: &TestConfig) -> Vec<(String, u32)> {
+    let native = config.native_size.unwrap_or(100);
+    match &config.downscale_sizes {
+        Some(sizes) => sizes.iter().map(|&size| (format!("downscale_{size}px"), size)).collect(),
+        None => (1..=4).map(|factor| (format!("downscale_{factor}x"), native * factor)).collect(),
+    }
+}
+
+/// Stress tests that run regardless of config, after the downscales, in
+/// [`stress_variants`] order. `quiet_zone` and `finder_integrity` aren't
+/// image variants.
+const FIXED_TESTS: [&str; 30] = [
+    "blur_light", "blur_heavy",
+    "contrast_up", "contrast_down", "contrast_strict_up", "contrast_strict_down",
+    "luminance_up", "luminance_down", "luminance_strict_up", "luminance_strict_down",
+    "gamma_up", "gamma_down", "gamma_strict_up", "gamma_strict_down",
+    "hue_up", "hue_down", "hue_strict_up", "hue_strict_down",
+    "saturation_up", "saturation_down", "saturation_strict_up", "saturation_strict_down",
+    "lighting_gradient", "occlude_center", "erasure", "halftone", "scan_mid", "scan_far",
+    "quiet_zone", "finder_integrity",
+];
+
+/// Names of every stress test `config` runs, as they appear in [`StressResults::tests`].
+pub fn stress_test_names(config: &TestConfig) -> Vec<String> {
+    downscale_targets(config)
+        .into_iter()
+        .map(|(name, _)| name)
+        .chain(FIXED_TESTS.iter().map(|name| name.to_string()))
+        .collect()
+}
+
+/// Check that every weight key names a stress test `config` runs, so a
+/// typo'd key doesn't silently skew the score. A test without a weight isn't
+/// an error: it just doesn't count, and [`crate::lint_config`] warns about it.
+///
+/// `downscale_1x`..`downscale_4x` are accepted even when `downscale_sizes`
+/// replaces them, so the default weights stay valid; `downscale_<N>px` tests
+/// fall back to [`crate::types::DEFAULT_DOWNSCALE_SIZE_WEIGHT`] and don't need a key.
+pub fn validate_weights(config: &TestConfig) -> Result<()> {
+    let (unknown, _) = weight_key_mismatches(config);
+    match unknown.into_iter().next() {
+        Some(key) => Err(QrScoreError::UnknownWeightKey { key }),
+        None => Ok(()),
+    }
+}
+
+/// Every weight key that names no stress test, and every stress test without
+/// a weight, by the rules of [`validate_weights`].
+pub(crate) fn weight_key_mismatches(config: &TestConfig) -> (Vec<String>, Vec<String>) {
+    let active = stress_test_names(config);
+    let multiplier = |key: &str| matches!(key, "downscale_1x" | "downscale_2x" | "downscale_3x" | "downscale_4x");
+
+    let unknown = config
+        .weights
+        .tests
+        .keys()
+        .filter(|key| !active.contains(key) && !multiplier(key))
+        .cloned()
+        .collect();
+    let missing = active.into_iter().filter(|name| config.weights.weight_of(name).is_none()).collect();
+    (unknown, missing)
+}
+
+/// Checks that measure the QR grid itself. rqrr only finds QR codes, so they
+/// are left out, along with their weight, for other symbologies.
+pub const QR_ONLY_TESTS: [&str; 2] = ["quiet_zone", "finder_integrity"];
+
+fn run_stress_tests(
+    img: &DynamicImage,
+    config: &TestConfig,
+    format: SymbolFormat,
+    location: Option<&GridLocation>,
+    deadline: Option<Instant>,
+) -> Result<StressResults> {
+    let contrast_ratio = measure_contrast(img, config.polarity);
+    let wcag_contrast = measure_wcag_contrast(img);
+    let edge_sharpness = measure_edge_sharpness(img);
+    let variants = stress_variants_until(img, config, location, deadline)?;
+
+    let mut tests: BTreeMap<String, TestOutcome> = variants
         .par_iter()
         .map(|(name, variant)| {
-            let passed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                try_decode(variant).is_ok()
+            crate::check_deadline(deadline)?;
+            let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                decode_outcome(try_decode(variant, config))
             }))
-            .unwrap_or(false);
-            (name.to_string(), passed)
+            .unwrap_or(TestOutcome::Panicked);
+            Ok((name.clone(), outcome))
         })
-        .collect();
+        .collect::<Result<_>>()?;
+
+    if format == SymbolFormat::Qr {
+        crate::check_deadline(deadline)?;
+        // Margins are measured in whole pixels, so allow one pixel of rounding slack
+        let quiet_zone = match measure_quiet_zone_at(img, location) {
+            None => TestOutcome::NoGrid,
+            Some(qz) => (qz.min + 1.0 / qz.module_size >= config.min_quiet_zone_modules).into(),
+        };
+        tests.insert("quiet_zone".into(), quiet_zone);
 
-    StressResults {
+        let finders = check_finder_patterns_at(img, location);
+        let finder_integrity = if finders.finders.is_empty() {
+            TestOutcome::NoGrid
+        } else {
+            finders.passes(config.finder_tolerance).into()
+        };
+        tests.insert("finder_integrity".into(), finder_integrity);
+    }
+
+    Ok(StressResults {
         tests,
         contrast_ratio,
+        wcag_contrast,
+        edge_sharpness,
+        verbose_outcomes: config.verbose_outcomes,
+    })
+}
+
+fn decode_outcome<T>(result: Result<T>) -> TestOutcome {
+    match result {
+        Ok(_) => TestOutcome::Pass,
+        Err(QrScoreError::UnreadableSymbol) => TestOutcome::DecodeError,
+        Err(QrScoreError::DecoderPanicked) => TestOutcome::Panicked,
+        Err(_) => TestOutcome::NoGrid,
     }
 }
 
+/// Only tests that were actually run count towards the total, so weights for
+/// variants a config doesn't produce (such as `downscale_2x` when
+/// `downscale_sizes` is set) neither add nor subtract.
 fn calculate_score(stress: &StressResults, weights: &crate::types::Weights) -> u8 {
-    let total_weight: u32 = weights.tests.values().sum::<u32>() + weights.contrast_ratio;
+    let run_weight: u32 = stress.tests.keys().filter_map(|name| weights.weight_of(name)).sum();
+    let total_weight = run_weight + weights.contrast_ratio + weights.edge_sharpness;
 
     if total_weight == 0 {
         return 0;
     }
 
     let test_score: f32 = stress.tests.iter()
-        .filter(|&(_, &passed)| passed)
-        .filter_map(|(name, _)| weights.tests.get(name))
-        .map(|&w| w as f32)
+        .filter(|&(_, outcome)| outcome.passed())
+        .filter_map(|(name, _)| weights.weight_of(name))
+        .map(|w| w as f32)
         .sum();
 
     let normalized = (stress.contrast_ratio / 0.7).clamp(0.0, 1.0);
-    let score = test_score + normalized * weights.contrast_ratio as f32;
+    // Full credit from 0.95 sharpness, none at 0.5 or below
+    let sharpness = ((stress.edge_sharpness - 0.5) / 0.45).clamp(0.0, 1.0);
+    let score = test_score
+        + normalized * weights.contrast_ratio as f32
+        + sharpness * weights.edge_sharpness as f32;
 
     ((score / total_weight as f32) * 100.0).round().min(100.0) as u8
 }
@@ -97,6 +302,28 @@ fn adjust_luminance(img: &DynamicImage, amount: i32) -> DynamicImage {
     img.brighten(amount)
 }
 
+/// `out = 255 * (in / 255)^gamma` through a 256-entry lookup table, so
+/// `powf` runs once per level rather than once per pixel. `gamma > 1`
+/// darkens the midtones, `gamma < 1` lifts them; black and white stay put.
+///
+/// Applied per channel: gamma doesn't commute with the luma conversion, so
+/// only a luma image stays luma.
+fn apply_gamma(img: &DynamicImage, gamma: f32) -> DynamicImage {
+    let lut: [u8; 256] = std::array::from_fn(|v| (255.0 * (v as f32 / 255.0).powf(gamma)).round() as u8);
+    match img {
+        DynamicImage::ImageLuma8(luma) => {
+            let mut luma = luma.clone();
+            luma.iter_mut().for_each(|v| *v = lut[*v as usize]);
+            DynamicImage::ImageLuma8(luma)
+        }
+        _ => {
+            let mut rgb = img.to_rgb8();
+            rgb.iter_mut().for_each(|v| *v = lut[*v as usize]);
+            DynamicImage::ImageRgb8(rgb)
+        }
+    }
+}
+
 fn shift_hue(img: &DynamicImage, degrees: f32) -> DynamicImage {
     img.huerotate(degrees as i32)
 }
@@ -128,6 +355,168 @@ fn adjust_saturation(img: &DynamicImage, amount: f32) -> DynamicImage {
     }
 }
 
+/// Radial vignette: full brightness at the center falling off to
+/// `1 - strength` at the corners, like a phone flash on a printed code.
+fn apply_lighting_gradient(img: &DynamicImage, strength: f32) -> DynamicImage {
+    let mut luma = img.to_luma8();
+    let (width, height) = luma.dimensions();
+    let strength = strength.clamp(0.0, 1.0);
+    let cx = width as f32 / 2.0;
+    let cy = height as f32 / 2.0;
+    let max_dist_sq = cx * cx + cy * cy;
+
+    if max_dist_sq == 0.0 {
+        return DynamicImage::ImageLuma8(luma);
+    }
+
+    for (x, y, px) in luma.enumerate_pixels_mut() {
+        let dx = x as f32 + 0.5 - cx;
+        let dy = y as f32 + 0.5 - cy;
+        let factor = 1.0 - strength * (dx * dx + dy * dy) / max_dist_sq;
+        px.0[0] = (px.0[0] as f32 * factor).round() as u8;
+    }
+    DynamicImage::ImageLuma8(luma)
+}
+
+/// 4x4 Bayer threshold matrix, values 0..16.
+const BAYER_4X4: [[u8; 4]; 4] = [
+    [0, 8, 2, 10],
+    [12, 4, 14, 6],
+    [3, 11, 1, 9],
+    [15, 7, 13, 5],
+];
+
+/// Screen to pure black and white with ordered dithering, as offset printing
+/// does. Each Bayer matrix entry covers a `cell`-pixel square, so the
+/// pattern repeats every `4 * cell` pixels. The fixed matrix keeps the result
+/// deterministic.
+fn apply_halftone(img: &DynamicImage, cell: u32) -> DynamicImage {
+    let cell = cell.max(1);
+    let mut luma = img.to_luma8();
+    for (x, y, px) in luma.enumerate_pixels_mut() {
+        let level = BAYER_4X4[(y / cell % 4) as usize][(x / cell % 4) as usize];
+        let threshold = (level as f32 + 0.5) * 255.0 / 16.0;
+        px.0[0] = if px.0[0] as f32 > threshold { 255 } else { 0 };
+    }
+    DynamicImage::ImageLuma8(luma)
+}
+
+/// Model a photo taken from a distance: downscale to `size`, then blur the
+/// result by `blur_per_factor` times the downscale factor, since a code far
+/// enough away to lose that much resolution is also out of the lens's sharp range.
+fn simulate_scan(img: &DynamicImage, size: u32, blur_per_factor: f32) -> DynamicImage {
+    let (w, h) = img.dimensions();
+    let factor = (w.max(h) as f32 / size.max(1) as f32).max(1.0);
+    apply_blur(&resize_to(img, size), blur_per_factor * factor)
+}
+
+/// Paint an opaque square covering `fraction` of the symbol area, centered on
+/// the detected QR (or the whole image if no grid was found), to model a logo.
+fn occlude_center(img: &DynamicImage, location: Option<&GridLocation>, fraction: f32, color: [u8; 3]) -> DynamicImage {
+    let mut luma = img.to_luma8();
+    let (width, height) = luma.dimensions();
+    let fill = Rgb(color).to_luma();
+
+    let (x0, y0, x1, y1) = location
+        .map(GridLocation::bounding_box)
+        .unwrap_or((0.0, 0.0, width as f32, height as f32));
+    let side = (x1 - x0).min(y1 - y0) * fraction.clamp(0.0, 1.0).sqrt();
+    let cx = (x0 + x1) / 2.0;
+    let cy = (y0 + y1) / 2.0;
+
+    let left = (cx - side / 2.0).round().max(0.0) as u32;
+    let top = (cy - side / 2.0).round().max(0.0) as u32;
+    let right = ((cx + side / 2.0).round().max(0.0) as u32).min(width);
+    let bottom = ((cy + side / 2.0).round().max(0.0) as u32).min(height);
+
+    for y in top..bottom {
+        for x in left..right {
+            luma.put_pixel(x, y, fill);
+        }
+    }
+    DynamicImage::ImageLuma8(luma)
+}
+
+/// Deterministic splitmix64 stream, so `erasure` blanks the same patches on
+/// every run with the same seed.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// Luma of the background: the light end of the image for dark-on-light
+/// codes, the dark end for light-on-dark.
+fn background_luma(luma: &DynamicImage, polarity: Polarity) -> u8 {
+    let luma = luma.to_luma8();
+    let mut histogram = [0u32; 256];
+    for px in luma.pixels() {
+        histogram[px.0[0] as usize] += 1;
+    }
+    let total: u32 = histogram.iter().sum();
+    let target = match polarity {
+        Polarity::LightOnDark => total / 20,
+        _ => total - total / 20,
+    };
+    let mut cumulative = 0;
+    for (value, &count) in histogram.iter().enumerate() {
+        cumulative += count;
+        if cumulative > target {
+            return value as u8;
+        }
+    }
+    255
+}
+
+/// Scuffs and tears: background-colored patches two modules square, placed
+/// at random within the detected QR until they add up to `fraction` of its
+/// area. Patches may overlap, so slightly less than that is actually lost.
+fn apply_erasure(
+    img: &DynamicImage,
+    location: Option<&GridLocation>,
+    fraction: f32,
+    seed: u64,
+    background: u8,
+) -> DynamicImage {
+    let mut luma = img.to_luma8();
+    let (width, height) = luma.dimensions();
+
+    let (x0, y0, x1, y1) = location
+        .map(GridLocation::bounding_box)
+        .unwrap_or((0.0, 0.0, width as f32, height as f32));
+    let (box_w, box_h) = (x1 - x0, y1 - y0);
+    let patch = location
+        .map_or(box_w.min(box_h) / 12.0, |l| 2.0 * l.module_size())
+        .max(1.0);
+    let count = (fraction.clamp(0.0, 1.0) * box_w * box_h / (patch * patch)).round() as u32;
+
+    let mut rng = SplitMix64(seed);
+    for _ in 0..count {
+        let left = x0 + rng.next_f32() * (box_w - patch).max(0.0);
+        let top = y0 + rng.next_f32() * (box_h - patch).max(0.0);
+        let (left, top) = (left.round().max(0.0) as u32, top.round().max(0.0) as u32);
+        let right = (left + patch.round() as u32).min(width);
+        let bottom = (top + patch.round() as u32).min(height);
+        for y in top..bottom {
+            for x in left..right {
+                luma.put_pixel(x, y, image::Luma([background]));
+            }
+        }
+    }
+    DynamicImage::ImageLuma8(luma)
+}
+
 fn srgb_linearize(v: u8) -> f32 {
     let s = v as f32 / 255.0;
     if s <= 0.03928 {
@@ -141,54 +530,228 @@ fn relative_luminance(r: u8, g: u8, b: u8) -> f32 {
     0.2126 * srgb_linearize(r) + 0.7152 * srgb_linearize(g) + 0.0722 * srgb_linearize(b)
 }
 
-fn measure_contrast(img: &DynamicImage) -> f32 {
+/// Relative luminance of every pixel, quantized to 1000 bins, with the pixel
+/// count. `None` for an empty image.
+fn luminance_histogram(img: &DynamicImage) -> Option<([u32; 1001], u32)> {
     let rgb = img.to_rgb8();
     let raw = rgb.as_raw();
 
     if raw.is_empty() {
-        return 0.0;
+        return None;
     }
 
-    // Compute relative luminance for every pixel, quantized to 1000 bins
     let mut histogram = [0u32; 1001];
-    let total = raw.len() / 3;
-
     for px in raw.chunks_exact(3) {
         let lum = relative_luminance(px[0], px[1], px[2]);
         let bin = (lum * 1000.0).round().min(1000.0) as usize;
         histogram[bin] += 1;
     }
 
-    // Find 5th and 95th percentile luminance
-    let p5_target = total as u32 / 20;
-    let p95_target = total as u32 - p5_target;
+    Some((histogram, (raw.len() / 3) as u32))
+}
 
+/// 5th and 95th percentile bins of a luminance histogram.
+fn percentile_bins(histogram: &[u32; 1001], total: u32) -> (usize, usize) {
+    let p5_target = total / 20;
+    percentile_bins_at(histogram, p5_target, total - p5_target)
+}
+
+/// First bins at which the cumulative pixel count reaches each target.
+fn percentile_bins_at(histogram: &[u32; 1001], p5_target: u32, p95_target: u32) -> (usize, usize) {
     let mut cumulative = 0u32;
-    let mut p5 = 0.0f32;
-    let mut p95 = 1.0f32;
+    let mut p5 = 0;
+    let mut p95 = 1000;
 
     for (i, &count) in histogram.iter().enumerate() {
         let prev = cumulative;
         cumulative += count;
         if prev < p5_target && cumulative >= p5_target {
-            p5 = i as f32 / 1000.0;
+            p5 = i;
         }
         if prev < p95_target && cumulative >= p95_target {
-            p95 = i as f32 / 1000.0;
+            p95 = i;
             break;
         }
     }
 
-    p95 - p5
+    (p5, p95)
+}
+
+/// 5th and 95th percentile relative luminance, or `None` for an empty image.
+fn luminance_percentiles(img: &DynamicImage) -> Option<(f32, f32)> {
+    let (histogram, total) = luminance_histogram(img)?;
+    let (p5, p95) = percentile_bins(&histogram, total);
+    Some((p5 as f32 / 1000.0, p95 as f32 / 1000.0))
+}
+
+/// Bin that best separates the dark and light clusters by Otsu's method, with
+/// bins up to and including it counting as dark. `None` if every pixel falls
+/// in one bin.
+fn otsu_threshold(histogram: &[u32; 1001], total: u32) -> Option<usize> {
+    let weighted_total: f64 = histogram.iter().enumerate().map(|(i, &c)| i as f64 * c as f64).sum();
+    let (mut dark, mut dark_weighted) = (0.0f64, 0.0f64);
+    let mut best: Option<(usize, f64)> = None;
+
+    for (i, &count) in histogram.iter().enumerate().take(1000) {
+        dark += count as f64;
+        dark_weighted += i as f64 * count as f64;
+        let light = total as f64 - dark;
+        if dark == 0.0 || light == 0.0 {
+            continue;
+        }
+        let gap = dark_weighted / dark - (weighted_total - dark_weighted) / light;
+        let between = dark * light * gap * gap;
+        if best.is_none_or(|(_, b)| between > b) {
+            best = Some((i, between));
+        }
+    }
+
+    best.map(|(i, _)| i)
+}
+
+/// `polarity`, with `Auto` taking whichever cluster has fewer pixels as the
+/// modules. A tie counts as dark-on-light.
+fn resolve_polarity(polarity: Polarity, dark: u32, total: u32) -> Polarity {
+    match polarity {
+        Polarity::Auto if dark as u64 * 2 > total as u64 => Polarity::LightOnDark,
+        Polarity::Auto => Polarity::DarkOnLight,
+        fixed => fixed,
+    }
+}
+
+/// Whether the modules are dark on light or light on dark, judging the
+/// luminance cluster with fewer pixels to be the modules. Never `Auto`.
+pub fn detect_polarity(img: &DynamicImage) -> Polarity {
+    image_polarity(img, Polarity::Auto)
 }
 
-pub fn grade_from_score(score: u8) -> &'static str {
-    match score {
-        80.. => "A",
-        60.. => "B",
-        40.. => "C",
-        20.. => "D",
-        _ => "F",
+/// `polarity` resolved against `img`; never `Auto`.
+fn image_polarity(img: &DynamicImage, polarity: Polarity) -> Polarity {
+    let Some((histogram, total)) = luminance_histogram(img) else {
+        return resolve_polarity(polarity, 0, 0);
+    };
+    let dark = otsu_threshold(&histogram, total).map_or(0, |t| histogram[..=t].iter().sum());
+    resolve_polarity(polarity, dark, total)
+}
+
+/// Spread between the dark (p5) and light (p95) luminance, 0-1.
+///
+/// When the modules cover less than a tenth of the image, as with a small
+/// code on a large card, the global percentile on the module side would land
+/// in the background and read as no contrast. So that percentile is moved
+/// inside the module cluster, at most halfway in. `polarity` says which
+/// cluster holds the modules, so a code and its inverse read the same.
+fn measure_contrast(img: &DynamicImage, polarity: Polarity) -> f32 {
+    let Some((histogram, total)) = luminance_histogram(img) else {
+        return 0.0;
+    };
+    let p5_target = total / 20;
+    let (mut low, mut high) = (p5_target, total - p5_target);
+
+    if let Some(threshold) = otsu_threshold(&histogram, total) {
+        let dark: u32 = histogram[..=threshold].iter().sum();
+        match resolve_polarity(polarity, dark, total) {
+            Polarity::LightOnDark => high = total - p5_target.min((total - dark) / 2),
+            _ => low = p5_target.min(dark / 2).max(1),
+        }
+    }
+
+    let (p5, p95) = percentile_bins_at(&histogram, low, high);
+    p95 as f32 / 1000.0 - p5 as f32 / 1000.0
+}
+
+/// Which pixels belong to the module cluster under `polarity`, or `None` if
+/// the image doesn't split into two clusters.
+fn module_mask(img: &DynamicImage, polarity: Polarity) -> Option<Vec<bool>> {
+    let (histogram, total) = luminance_histogram(img)?;
+    let threshold = otsu_threshold(&histogram, total)?;
+    let dark: u32 = histogram[..=threshold].iter().sum();
+    let modules_dark = resolve_polarity(polarity, dark, total) != Polarity::LightOnDark;
+    let rgb = img.to_rgb8();
+    Some(
+        rgb.as_raw()
+            .chunks_exact(3)
+            .map(|px| {
+                let bin = (relative_luminance(px[0], px[1], px[2]) * 1000.0).round() as usize;
+                (bin <= threshold) == modules_dark
+            })
+            .collect(),
+    )
+}
+
+/// `transformed` on module pixels and `original` everywhere else, so color
+/// shifts model drift in the module color against a fixed background.
+fn on_modules(original: &DynamicImage, transformed: DynamicImage, mask: Option<&[bool]>) -> DynamicImage {
+    let Some(mask) = mask else {
+        return transformed;
+    };
+    let mut out = transformed.to_rgb8();
+    let original = original.to_rgb8();
+    for ((px, orig), &module) in out.pixels_mut().zip(original.pixels()).zip(mask) {
+        if !module {
+            *px = *orig;
+        }
+    }
+    DynamicImage::ImageRgb8(out)
+}
+
+/// WCAG contrast ratio `(L1 + 0.05) / (L2 + 0.05)` between the light (p95) and
+/// dark (p5) modules, ranging from 1.0 (no contrast) to 21.0 (pure black on white).
+pub fn measure_wcag_contrast(img: &DynamicImage) -> f32 {
+    match luminance_percentiles(img) {
+        Some((p5, p95)) => (p95 + 0.05) / (p5 + 0.05),
+        None => 1.0,
+    }
+}
+
+/// How crisp the module boundaries are, from 1.0 (every pixel is at the dark
+/// or light level) down to 0.0 (every pixel is in between).
+///
+/// Counts the pixels whose luminance falls in the 10–90% band between the dark
+/// (p5) and light (p95) levels, the usual rise-time band for an edge. Rounded
+/// corners, gradients and anti-aliasing all add mid-band pixels, which
+/// binarizers struggle to place once the code is downscaled. A uniform image
+/// has no edges and counts as sharp.
+pub fn measure_edge_sharpness(img: &DynamicImage) -> f32 {
+    let Some((histogram, total)) = luminance_histogram(img) else {
+        return 1.0;
+    };
+    let (p5, p95) = percentile_bins(&histogram, total);
+    let range = p95.saturating_sub(p5);
+    if range < 10 {
+        return 1.0;
+    }
+    let (lo, hi) = (p5 + range / 10, p95 - range / 10);
+    let mid: u32 = histogram[lo + 1..hi].iter().sum();
+    1.0 - mid as f32 / total as f32
+}
+
+impl GradeThresholds {
+    pub fn grade_from_score(&self, score: u8) -> &'static str {
+        self.grade_of(score).letter()
+    }
+
+    /// [`grade_from_score`](Self::grade_from_score) as an [`IsoGrade`], which
+    /// orders from F (lowest) to A.
+    pub fn grade_of(&self, score: u8) -> IsoGrade {
+        match score {
+            s if s >= self.a => IsoGrade::A,
+            s if s >= self.b => IsoGrade::B,
+            s if s >= self.c => IsoGrade::C,
+            s if s >= self.d => IsoGrade::D,
+            _ => IsoGrade::F,
+        }
+    }
+
+    /// Thresholds must strictly decrease from A to D, otherwise some grades are unreachable.
+    pub fn validate(&self) -> Result<()> {
+        if self.a > self.b && self.b > self.c && self.c > self.d {
+            return Ok(());
+        }
+        Err(QrScoreError::InvalidConfig(format!(
+            "grade thresholds must be strictly decreasing (a > b > c > d), got a={} b={} c={} d={}",
+            self.a, self.b, self.c, self.d
+        )))
     }
 }
 
@@ -205,14 +768,17 @@ mod tests {
     }
 
     fn all_pass_stress() -> StressResults {
-        let tests: BTreeMap<String, bool> = Weights::default()
+        let tests: BTreeMap<String, TestOutcome> = Weights::default()
             .tests
             .keys()
-            .map(|k| (k.clone(), true))
+            .map(|k| (k.clone(), TestOutcome::Pass))
             .collect();
         StressResults {
             tests,
             contrast_ratio: 1.0,
+            wcag_contrast: 21.0,
+            edge_sharpness: 1.0,
+            verbose_outcomes: false,
         }
     }
 
@@ -242,13 +808,422 @@ mod tests {
         assert!(score >= 50, "clean QR should score >= 50, got {}", score);
     }
 
+    #[test]
+    fn quiet_zone_included_in_stress_tests() {
+        let img = create_test_qr_image();
+        let (stress, _) = validate(&img, &TestConfig::default());
+        assert_eq!(stress.tests.get("quiet_zone"), Some(&TestOutcome::Pass));
+
+        let config = TestConfig { min_quiet_zone_modules: 5.0, ..TestConfig::default() };
+        let (stress, _) = validate(&img, &config);
+        assert_eq!(stress.tests.get("quiet_zone"), Some(&TestOutcome::Fail));
+    }
+
+    #[test]
+    fn stress_outcomes_distinguish_failure_reasons() {
+        let img = create_test_qr_image();
+        let (stress, _) = validate(&img, &TestConfig::default());
+        assert_eq!(stress.tests["blur_light"], TestOutcome::Pass);
+
+        let (stress, _) = validate(&DynamicImage::new_luma8(200, 200), &TestConfig::default());
+        assert!(stress.tests.values().all(|&o| o == TestOutcome::NoGrid), "{:?}", stress.tests);
+    }
+
+    #[test]
+    fn decode_outcome_maps_errors() {
+        assert_eq!(decode_outcome(Ok(())), TestOutcome::Pass);
+        assert_eq!(decode_outcome::<()>(Err(QrScoreError::DecodeFailed)), TestOutcome::NoGrid);
+        assert_eq!(decode_outcome::<()>(Err(QrScoreError::UnreadableSymbol)), TestOutcome::DecodeError);
+        assert_eq!(decode_outcome::<()>(Err(QrScoreError::DecoderPanicked)), TestOutcome::Panicked);
+    }
+
+    #[test]
+    fn lighting_gradient_darkens_edges_only() {
+        let img = DynamicImage::ImageLuma8(image::GrayImage::from_pixel(101, 101, image::Luma([200])));
+        let lit = apply_lighting_gradient(&img, 0.5).to_luma8();
+        assert_eq!(lit.get_pixel(50, 50).0, [200]);
+        let corner = lit.get_pixel(0, 0).0[0];
+        assert!((99..=103).contains(&corner), "corner should be ~half brightness, got {}", corner);
+        assert_eq!(apply_lighting_gradient(&img, 0.0).to_luma8(), img.to_luma8());
+    }
+
+    #[test]
+    fn occlusion_centered_on_symbol() {
+        // Pad asymmetrically so the image center is away from the symbol center
+        let qr = create_test_qr_image().to_luma8();
+        let mut padded = image::GrayImage::from_pixel(qr.width() + 200, qr.height(), image::Luma([255]));
+        image::imageops::overlay(&mut padded, &qr, 0, 0);
+        let img = DynamicImage::ImageLuma8(padded);
+
+        let location = locate_grid(&img).unwrap();
+        // Red's luma is mid-grey, distinct from the black and white modules
+        let fill = Rgb([255, 0, 0]).to_luma();
+        let occluded = occlude_center(&img, Some(&location), 0.15, [255, 0, 0]).to_luma8();
+        let (x0, y0, x1, y1) = location.bounding_box();
+        let center = (((x0 + x1) / 2.0) as u32, ((y0 + y1) / 2.0) as u32);
+        assert_eq!(*occluded.get_pixel(center.0, center.1), fill);
+        assert_ne!(*occluded.get_pixel(img.width() / 2, img.height() / 2), fill);
+
+        let painted = occluded.pixels().filter(|&&p| p == fill).count() as f32;
+        let area = (x1 - x0) * (y1 - y0);
+        assert!((painted / area - 0.15).abs() < 0.02, "covered {}", painted / area);
+    }
+
+    #[test]
+    fn occlusion_survivable_with_high_ec() {
+        let code = qrcode::QrCode::with_error_correction_level(b"https://example.com", qrcode::EcLevel::H).unwrap();
+        let img = DynamicImage::ImageLuma8(code.render::<image::Luma<u8>>().build());
+        let location = locate_grid(&img);
+        let occluded = occlude_center(&img, location.as_ref(), 0.15, [0, 0, 0]);
+        assert!(try_decode(&occluded, &TestConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn erasure_is_seeded_and_stays_in_the_symbol() {
+        let qr = create_test_qr_image().to_luma8();
+        let mut padded = image::GrayImage::from_pixel(qr.width() + 200, qr.height(), image::Luma([255]));
+        image::imageops::overlay(&mut padded, &qr, 0, 0);
+        let img = DynamicImage::ImageLuma8(padded);
+        let location = locate_grid(&img).unwrap();
+
+        // Mid grey stands out from both module colours
+        let erased = apply_erasure(&img, Some(&location), 0.1, 7, 128).to_luma8();
+        assert_eq!(erased, apply_erasure(&img, Some(&location), 0.1, 7, 128).to_luma8());
+        assert_ne!(erased, apply_erasure(&img, Some(&location), 0.1, 8, 128).to_luma8());
+
+        let (x0, y0, x1, y1) = location.bounding_box();
+        let painted: Vec<(u32, u32)> = erased
+            .enumerate_pixels()
+            .filter(|(_, _, p)| p.0[0] == 128)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        assert!(!painted.is_empty());
+        assert!(painted.iter().all(|&(x, y)| (x as f32) < x1 + 1.0 && (y as f32) < y1 + 1.0
+            && x as f32 >= x0 - 1.0 && y as f32 >= y0 - 1.0));
+        let area = (x1 - x0) * (y1 - y0);
+        assert!(painted.len() as f32 / area <= 0.1 + 0.01, "covered {}", painted.len() as f32 / area);
+
+        assert_eq!(apply_erasure(&img, Some(&location), 0.0, 7, 128).to_luma8(), img.to_luma8());
+    }
+
+    #[test]
+    fn erasure_paints_background_for_inverted_codes() {
+        let img = create_test_qr_image();
+        assert_eq!(background_luma(&img.to_luma8().into(), Polarity::DarkOnLight), 255);
+        let mut inverted = img.clone();
+        inverted.invert();
+        assert_eq!(background_luma(&inverted, Polarity::LightOnDark), 0);
+    }
+
+    #[test]
+    fn erasure_survivable_at_default_fraction() {
+        let img = create_test_qr_image();
+        let config = TestConfig::default();
+        let erased = apply_erasure(&img, locate_grid(&img).as_ref(), config.erasure_fraction, config.erasure_seed, 255);
+        assert_ne!(erased.to_luma8(), img.to_luma8());
+        assert!(try_decode(&erased, &config).is_ok());
+    }
+
+    #[test]
+    fn downscale_sizes_replace_multiplier_variants() {
+        let img = create_test_qr_image();
+        let config = TestConfig { downscale_sizes: Some(vec![60, 90]), ..TestConfig::default() };
+        let names: Vec<String> = stress_variants(&img, &config).into_iter().map(|(n, _)| n).collect();
+        assert_eq!(&names[..2], ["downscale_60px", "downscale_90px"]);
+        assert_eq!(names.iter().filter(|n| n.starts_with("downscale_")).count(), 2);
+
+        let (stress, score) = validate(&img, &config);
+        assert!(stress.tests.contains_key("downscale_90px"));
+        assert!(!stress.tests.contains_key("downscale_1x"));
+        assert!(score > 0);
+    }
+
+    #[test]
+    fn score_downscale_size_uses_fallback_weight() {
+        let mut stress = all_pass_stress();
+        for key in ["downscale_1x", "downscale_2x", "downscale_3x", "downscale_4x"] {
+            stress.tests.remove(key);
+        }
+        stress.tests.insert("downscale_60px".into(), TestOutcome::Pass);
+        assert_eq!(calculate_score(&stress, &Weights::default()), 100);
+
+        stress.tests.insert("downscale_60px".into(), TestOutcome::NoGrid);
+        assert!(calculate_score(&stress, &Weights::default()) < 100);
+    }
+
+    #[test]
+    fn expired_deadline_times_out() {
+        let img = create_test_qr_image();
+        let result = validate_until(&img, &TestConfig::default(), SymbolFormat::Qr, None, Some(Instant::now()));
+        assert!(matches!(result, Err(QrScoreError::TimedOut)));
+
+        // Generation stops too, not just the decodes
+        let result = stress_variants_until(&img, &TestConfig::default(), None, Some(Instant::now()));
+        assert!(matches!(result, Err(QrScoreError::TimedOut)));
+    }
+
+    #[test]
+    fn stress_test_names_match_scored_tests() {
+        let img = create_test_qr_image();
+        for config in [
+            TestConfig::default(),
+            TestConfig { downscale_sizes: Some(vec![60, 90]), ..TestConfig::default() },
+        ] {
+            let mut expected = stress_test_names(&config);
+            expected.sort();
+            let (stress, _) = validate(&img, &config);
+            assert_eq!(stress.tests.into_keys().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn validate_weights_accepts_defaults() {
+        assert!(validate_weights(&TestConfig::default()).is_ok());
+        let config = TestConfig { downscale_sizes: Some(vec![60]), ..TestConfig::default() };
+        assert!(validate_weights(&config).is_ok());
+    }
+
+    #[test]
+    fn validate_weights_rejects_unknown_key() {
+        let mut config = TestConfig::default();
+        let w = config.weights.tests.remove("downscale_1x").unwrap();
+        config.weights.tests.insert("downscal_1x".into(), w);
+        match validate_weights(&config) {
+            Err(QrScoreError::UnknownWeightKey { key }) => assert_eq!(key, "downscal_1x"),
+            other => panic!("expected UnknownWeightKey, got {:?}", other),
+        }
+
+        // A px key only counts when that size is configured
+        let config = TestConfig::builder().weight("downscale_61px", 1).build();
+        assert!(matches!(validate_weights(&config), Err(QrScoreError::UnknownWeightKey { .. })));
+    }
+
+    #[test]
+    fn validate_weights_allows_missing_key() {
+        let mut config = TestConfig::default();
+        config.weights.tests.remove("scan_far");
+        assert!(validate_weights(&config).is_ok());
+        assert_eq!(weight_key_mismatches(&config).1, ["scan_far"]);
+    }
+
+    #[test]
+    fn gamma_maps_midtones_and_keeps_extremes() {
+        let img = DynamicImage::ImageLuma8(image::GrayImage::from_raw(3, 1, vec![0, 128, 255]).unwrap());
+        let darker = apply_gamma(&img, 2.0).to_luma8().into_raw();
+        assert_eq!(darker, vec![0, 64, 255]);
+        let lighter = apply_gamma(&img, 0.5).to_luma8().into_raw();
+        assert_eq!(lighter, vec![0, 181, 255]);
+        assert_eq!(apply_gamma(&img, 1.0).to_luma8().into_raw(), vec![0, 128, 255]);
+    }
+
+    #[test]
+    fn gamma_applies_per_channel_on_colour() {
+        // Pure red keeps its channels, and so its luma, under gamma; taking
+        // luma first would darken it from 76 to 41
+        let red = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([255, 0, 0])));
+        let gamma = apply_gamma(&red, 1.5);
+        assert_eq!(gamma.to_rgb8().get_pixel(0, 0), &Rgb([255, 0, 0]));
+        assert_eq!(gamma.to_luma8().get_pixel(0, 0).0[0], red.to_luma8().get_pixel(0, 0).0[0]);
+
+        let mixed = DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb([128, 255, 0])));
+        assert_eq!(apply_gamma(&mixed, 2.0).to_rgb8().get_pixel(0, 0), &Rgb([64, 255, 0]));
+
+        // Coloured inputs get the colour variant in stress_variants
+        let variants = stress_variants(&red, &TestConfig::default());
+        let gamma_up = &variants.iter().find(|(n, _)| n == "gamma_up").unwrap().1;
+        assert_eq!(gamma_up.to_rgb8(), red.to_rgb8());
+    }
+
+    #[test]
+    fn halftone_is_bilevel_and_deterministic() {
+        let img = DynamicImage::ImageLuma8(image::GrayImage::from_pixel(16, 16, image::Luma([128])));
+        let screened = apply_halftone(&img, 2).to_luma8();
+        assert!(screened.pixels().all(|p| p.0[0] == 0 || p.0[0] == 255));
+        // Mid grey turns on half the dots
+        assert_eq!(screened.pixels().filter(|p| p.0[0] == 255).count(), 128);
+        // Each matrix entry spans a 2x2 block
+        assert_eq!(screened.get_pixel(0, 0), screened.get_pixel(1, 1));
+        assert_eq!(screened, apply_halftone(&img, 2).to_luma8());
+
+        let qr = create_test_qr_image();
+        assert!(try_decode(&apply_halftone(&qr, 2), &TestConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn simulate_scan_downscales_then_blurs() {
+        let img = create_test_qr_image();
+        let scanned = simulate_scan(&img, 100, 0.25);
+        assert_eq!(scanned.width().max(scanned.height()), 100);
+        assert_ne!(scanned.to_luma8(), resize_to(&img, 100).to_luma8());
+        assert!(try_decode(&scanned, &TestConfig::default()).is_ok());
+
+        // Far enough away the blur swamps the modules entirely
+        assert!(try_decode(&simulate_scan(&img, 40, 0.5), &TestConfig::default()).is_err());
+    }
+
+    #[test]
+    fn luminance_variants_are_luma() {
+        let img = DynamicImage::ImageRgb8(create_test_qr_image().to_rgb8());
+        for (name, variant) in stress_variants(&img, &TestConfig::default()) {
+            let color = name.starts_with("hue") || name.starts_with("saturation");
+            assert_eq!(variant.as_luma8().is_none(), color, "{} has unexpected color type", name);
+        }
+    }
+
+    #[test]
+    fn colour_inputs_keep_rgb_path() {
+        let qr = create_test_qr_image().to_luma8();
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(qr.width(), qr.height(), |x, y| {
+            if qr.get_pixel(x, y).0[0] < 128 { Rgb([20, 40, 230]) } else { Rgb([250, 230, 40]) }
+        }));
+        assert!(!is_grey(&img));
+
+        let config = TestConfig::default();
+        let variants: BTreeMap<String, DynamicImage> = stress_variants(&img, &config).into_iter().collect();
+        let expected = [
+            ("contrast_strict_up", adjust_contrast(&img, config.contrast_strict)),
+            ("luminance_strict_up", adjust_luminance(&img, config.luminance_strict)),
+            ("blur_heavy", apply_blur(&img, config.blur_heavy_sigma)),
+            ("downscale_1x", resize_to(&img, 100)),
+        ];
+        for (name, rgb) in expected {
+            assert_eq!(variants[name].to_rgb8(), rgb.to_rgb8(), "{} left the RGB path", name);
+        }
+
+        // Clamping per channel is why: blue saturates where its luma doesn't
+        let luma = DynamicImage::ImageLuma8(img.to_luma8());
+        assert_ne!(
+            adjust_luminance(&img, config.luminance_strict).to_luma8(),
+            adjust_luminance(&luma, config.luminance_strict).to_luma8()
+        );
+    }
+
+    #[test]
+    fn luma_variants_decode_like_rgb_variants() {
+        let img = DynamicImage::ImageRgb8(create_test_qr_image().to_rgb8());
+        let config = TestConfig::default();
+        let luma = DynamicImage::ImageLuma8(img.to_luma8());
+        let pairs = [
+            (apply_blur(&img, 2.0), apply_blur(&luma, 2.0)),
+            (adjust_contrast(&img, -50.0), adjust_contrast(&luma, -50.0)),
+            (adjust_luminance(&img, 40), adjust_luminance(&luma, 40)),
+            (resize_to(&img, 100), resize_to(&luma, 100)),
+        ];
+        for (rgb, luma) in pairs {
+            assert_eq!(try_decode(&rgb, &config).is_ok(), try_decode(&luma, &config).is_ok());
+        }
+    }
+
+    /// Time the blur, contrast, luminance and downscale variants plus their
+    /// decodes on a version-10 code, through the shared luma path
+    /// [`stress_variants`] takes and through the RGB round-trip it replaced
+    /// (transform in RGB, let `try_decode` convert each variant). Grey inputs
+    /// should take about half as long; colour ones take the RGB path either
+    /// way, so they only show the cost of checking `is_grey`.
+    #[test]
+    #[ignore = "timing comparison; run with `cargo test --release -- --ignored --nocapture`"]
+    fn luma_path_timing_on_version_10() {
+        let code = qrcode::QrCode::with_version(
+            b"https://example.com/a/much/longer/path/to/fill/a/version/ten/code",
+            qrcode::Version::Normal(10),
+            qrcode::EcLevel::M,
+        )
+        .unwrap();
+        let qr = code.render::<image::Luma<u8>>().module_dimensions(6, 6).build();
+        let grey = DynamicImage::ImageRgb8(DynamicImage::ImageLuma8(qr.clone()).to_rgb8());
+        let colour = DynamicImage::ImageRgb8(RgbImage::from_fn(qr.width(), qr.height(), |x, y| {
+            if qr.get_pixel(x, y).0[0] < 128 { Rgb([20, 40, 120]) } else { Rgb([250, 245, 230]) }
+        }));
+        let config = TestConfig::default();
+        let run = |src: &DynamicImage| {
+            let variants = [
+                apply_blur(src, config.blur_light_sigma),
+                apply_blur(src, config.blur_heavy_sigma),
+                adjust_contrast(src, config.contrast),
+                adjust_contrast(src, -config.contrast_strict),
+                adjust_luminance(src, config.luminance),
+                adjust_luminance(src, -config.luminance_strict),
+                resize_to(src, 200),
+                resize_to(src, 400),
+            ];
+            variants.iter().filter(|v| try_decode(v, &config).is_ok()).count()
+        };
+        let time = |f: &dyn Fn() -> usize| {
+            let start = Instant::now();
+            let decoded: usize = (0..5).map(|_| f()).sum();
+            (start.elapsed(), decoded)
+        };
+
+        for (name, img) in [("grey", &grey), ("colour", &colour)] {
+            let (round_trip, expected) = time(&|| run(img));
+            let (shared, decoded) = time(&|| {
+                let luma = DynamicImage::ImageLuma8(img.to_luma8());
+                run(if is_grey(img) { &luma } else { img })
+            });
+            assert_eq!(decoded, expected, "{}: the paths decode differently", name);
+            eprintln!("{name}: rgb round-trip {round_trip:?}, shared luma {shared:?}");
+            if name == "grey" {
+                assert!(shared < round_trip, "grey: shared luma {:?} vs round-trip {:?}", shared, round_trip);
+            }
+        }
+    }
+
     #[test]
     fn contrast_measurement_bw() {
         let img = create_test_qr_image();
-        let ratio = measure_contrast(&img);
+        let ratio = measure_contrast(&img, Polarity::Auto);
         assert!(ratio > 0.9, "B&W QR contrast should be near 1.0, got {}", ratio);
     }
 
+    #[test]
+    fn wcag_contrast_bw_is_max() {
+        let img = create_test_qr_image();
+        let ratio = measure_wcag_contrast(&img);
+        assert!((ratio - 21.0).abs() < 0.01, "B&W QR WCAG contrast should be 21, got {}", ratio);
+    }
+
+    #[test]
+    fn wcag_contrast_uniform_image_is_one() {
+        let img = DynamicImage::new_rgb8(100, 100);
+        let ratio = measure_wcag_contrast(&img);
+        assert!((ratio - 1.0).abs() < 0.01, "uniform image WCAG contrast should be 1, got {}", ratio);
+    }
+
+    #[test]
+    fn edge_sharpness_crisp_render_is_one() {
+        assert_eq!(measure_edge_sharpness(&create_test_qr_image()), 1.0);
+        assert_eq!(measure_edge_sharpness(&DynamicImage::new_rgb8(100, 100)), 1.0);
+    }
+
+    #[test]
+    fn edge_sharpness_drops_with_blur() {
+        let img = create_test_qr_image();
+        let light = measure_edge_sharpness(&img.blur(1.0));
+        let heavy = measure_edge_sharpness(&img.blur(4.0));
+        assert!(light < 0.95, "blurred edges should be soft, got {}", light);
+        assert!(heavy < light, "heavier blur should be softer: {} vs {}", heavy, light);
+    }
+
+    #[test]
+    fn edge_sharpness_scores_only_when_weighted() {
+        let mut stress = all_pass_stress();
+        stress.edge_sharpness = 0.5;
+        assert_eq!(calculate_score(&stress, &Weights::default()), 100);
+
+        let weights = Weights { edge_sharpness: 10, ..Weights::default() };
+        assert_eq!(calculate_score(&stress, &weights), 93);
+        stress.edge_sharpness = 0.97;
+        assert_eq!(calculate_score(&stress, &weights), 100);
+    }
+
+    #[test]
+    fn wcag_contrast_does_not_change_score() {
+        let mut stress = all_pass_stress();
+        let before = calculate_score(&stress, &Weights::default());
+        stress.wcag_contrast = 1.0;
+        assert_eq!(calculate_score(&stress, &Weights::default()), before);
+    }
+
     #[test]
     fn score_zero_total_weight_is_zero() {
         let mut weights = Weights::default();
@@ -262,7 +1237,7 @@ mod tests {
         let mut stress = all_pass_stress();
         // fail all downscale tests
         for key in ["downscale_1x", "downscale_2x", "downscale_3x", "downscale_4x"] {
-            stress.tests.insert(key.to_string(), false);
+            stress.tests.insert(key.to_string(), TestOutcome::NoGrid);
         }
         let score = calculate_score(&stress, &Weights::default());
         assert!(score > 0 && score < 100, "partial pass should score between 0 and 100, got {}", score);
@@ -272,21 +1247,95 @@ mod tests {
     fn contrast_measurement_uniform_image() {
         // A solid grey image has no contrast spread — p95 and p5 converge
         let img = DynamicImage::new_rgb8(100, 100); // all black
-        let ratio = measure_contrast(&img);
+        let ratio = measure_contrast(&img, Polarity::Auto);
         assert!(ratio < 0.01, "uniform image contrast should be near 0, got {}", ratio);
     }
 
+    /// The test QR pasted into the middle of a 1200px canvas of `background`.
+    fn small_code_on_canvas(invert: bool) -> DynamicImage {
+        let mut qr = create_test_qr_image().to_luma8();
+        let mut canvas = image::GrayImage::from_pixel(1200, 1200, image::Luma([255]));
+        if invert {
+            image::imageops::invert(&mut qr);
+            image::imageops::invert(&mut canvas);
+        }
+        image::imageops::overlay(&mut canvas, &qr, 468, 468);
+        DynamicImage::ImageLuma8(canvas)
+    }
+
+    #[test]
+    fn detect_polarity_takes_minority_as_modules() {
+        assert_eq!(detect_polarity(&small_code_on_canvas(false)), Polarity::DarkOnLight);
+        assert_eq!(detect_polarity(&small_code_on_canvas(true)), Polarity::LightOnDark);
+        assert_eq!(detect_polarity(&DynamicImage::new_rgb8(10, 10)), Polarity::DarkOnLight);
+    }
+
+    #[test]
+    fn contrast_sparse_modules_not_lost_in_background() {
+        // Auto reads a code and its inverse the same
+        let dark_on_light = small_code_on_canvas(false);
+        let ratio = measure_contrast(&dark_on_light, Polarity::Auto);
+        assert!(ratio > 0.9, "sparse dark modules read as {}", ratio);
+        assert_eq!(ratio, measure_contrast(&small_code_on_canvas(true), Polarity::Auto));
+        assert_eq!(ratio, measure_contrast(&dark_on_light, Polarity::DarkOnLight));
+
+        // Declaring the wrong cluster as the modules leaves them at the global p5
+        let ratio = measure_contrast(&dark_on_light, Polarity::LightOnDark);
+        assert!(ratio < 0.01, "mislabelled polarity should find no spread, got {}", ratio);
+    }
+
+    #[test]
+    fn color_variants_recolor_modules_only() {
+        let qr = create_test_qr_image().to_luma8();
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(qr.width(), qr.height(), |x, y| {
+            if qr.get_pixel(x, y).0[0] < 128 { Rgb([180, 20, 20]) } else { Rgb([200, 220, 255]) }
+        }));
+        let background = *img.to_rgb8().get_pixel(0, 0);
+        let module = *img.to_rgb8().get_pixel(36, 36);
+
+        let config = TestConfig::default();
+        let variants = stress_variants(&img, &config);
+        let hue = variants.iter().find(|(n, _)| n == "hue_strict_up").unwrap().1.to_rgb8();
+        assert_eq!(*hue.get_pixel(0, 0), background);
+        assert_ne!(*hue.get_pixel(36, 36), module);
+
+        let flipped = TestConfig { polarity: Polarity::LightOnDark, ..TestConfig::default() };
+        let variants = stress_variants(&img, &flipped);
+        let hue = variants.iter().find(|(n, _)| n == "hue_strict_up").unwrap().1.to_rgb8();
+        assert_ne!(*hue.get_pixel(0, 0), background);
+        assert_eq!(*hue.get_pixel(36, 36), module);
+    }
+
     #[test]
     fn grade_boundaries() {
-        assert_eq!(grade_from_score(100), "A");
-        assert_eq!(grade_from_score(80), "A");
-        assert_eq!(grade_from_score(79), "B");
-        assert_eq!(grade_from_score(60), "B");
-        assert_eq!(grade_from_score(59), "C");
-        assert_eq!(grade_from_score(40), "C");
-        assert_eq!(grade_from_score(39), "D");
-        assert_eq!(grade_from_score(20), "D");
-        assert_eq!(grade_from_score(19), "F");
-        assert_eq!(grade_from_score(0), "F");
+        let g = GradeThresholds::default();
+        assert_eq!(g.grade_from_score(100), "A");
+        assert_eq!(g.grade_from_score(80), "A");
+        assert_eq!(g.grade_from_score(79), "B");
+        assert_eq!(g.grade_from_score(60), "B");
+        assert_eq!(g.grade_from_score(59), "C");
+        assert_eq!(g.grade_from_score(40), "C");
+        assert_eq!(g.grade_from_score(39), "D");
+        assert_eq!(g.grade_from_score(20), "D");
+        assert_eq!(g.grade_from_score(19), "F");
+        assert_eq!(g.grade_from_score(0), "F");
+    }
+
+    #[test]
+    fn grade_custom_thresholds() {
+        let g = GradeThresholds { a: 90, b: 75, c: 50, d: 25 };
+        assert_eq!(g.grade_from_score(90), "A");
+        assert_eq!(g.grade_from_score(89), "B");
+        assert_eq!(g.grade_from_score(74), "C");
+        assert_eq!(g.grade_from_score(24), "F");
+    }
+
+    #[test]
+    fn grade_thresholds_must_decrease() {
+        assert!(GradeThresholds::default().validate().is_ok());
+        let equal = GradeThresholds { a: 60, b: 60, c: 40, d: 20 };
+        assert!(matches!(equal.validate(), Err(QrScoreError::InvalidConfig(_))));
+        let inverted = GradeThresholds { a: 20, b: 40, c: 60, d: 80 };
+        assert!(matches!(inverted.validate(), Err(QrScoreError::InvalidConfig(_))));
     }
 }
\ No newline at end of file
@@ -0,0 +1,76 @@
+use crate::types::QrMetadata;
+
+/// Constants behind [`recommend_min_size_mm`].
+///
+/// The print industry's 10:1 rule says a QR code should be at least a tenth
+/// of the scanning distance wide. That rule of thumb was calibrated on small
+/// codes of roughly 25 modules (version 2), so what it really fixes is the
+/// module pitch: `distance / (distance_ratio * reference_modules)`, or about
+/// 4 milliradians of viewing angle per module with the defaults. Denser codes
+/// need proportionally more width to keep that pitch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizingParams {
+    /// Scan distance divided by code width for the reference code.
+    pub distance_ratio: f32,
+    /// Modules per side of the code the ratio was calibrated on.
+    pub reference_modules: u32,
+}
+
+impl Default for SizingParams {
+    fn default() -> Self {
+        Self {
+            distance_ratio: 10.0,
+            reference_modules: 25,
+        }
+    }
+}
+
+/// Minimum printed width in millimetres of the symbol (excluding the quiet
+/// zone) for a code scanned from `scan_distance_mm`, using [`SizingParams::default`].
+pub fn recommend_min_size_mm(metadata: &QrMetadata, scan_distance_mm: f32) -> f32 {
+    recommend_min_size_mm_with(metadata, scan_distance_mm, &SizingParams::default())
+}
+
+/// [`recommend_min_size_mm`] with explicit constants. When the version is
+/// unknown the code is assumed to be `reference_modules` wide, which reduces
+/// to the plain distance ratio.
+pub fn recommend_min_size_mm_with(metadata: &QrMetadata, scan_distance_mm: f32, params: &SizingParams) -> f32 {
+    let reference = params.reference_modules.max(1) as f32;
+    let modules = metadata.modules().map_or(reference, |m| m as f32);
+    let module_pitch_mm = scan_distance_mm / (params.distance_ratio * reference);
+    module_pitch_mm * modules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ErrorCorrectionLevel;
+
+    fn meta(version: Option<u8>) -> QrMetadata {
+        QrMetadata { error_correction: ErrorCorrectionLevel::M, version }
+    }
+
+    #[test]
+    fn reference_code_follows_ten_to_one_rule() {
+        assert!((recommend_min_size_mm(&meta(Some(2)), 300.0) - 30.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn denser_codes_need_more_width() {
+        // version 10 is 57 modules: 57 * 250 / (10 * 25)
+        assert!((recommend_min_size_mm(&meta(Some(10)), 250.0) - 57.0).abs() < 1e-4);
+        assert!(recommend_min_size_mm(&meta(Some(1)), 250.0) < recommend_min_size_mm(&meta(Some(2)), 250.0));
+    }
+
+    #[test]
+    fn unknown_version_uses_reference() {
+        assert!((recommend_min_size_mm(&meta(None), 500.0) - 50.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn params_are_overridable() {
+        let params = SizingParams { distance_ratio: 5.0, reference_modules: 21 };
+        // version 1 is 21 modules, so this is plain distance / 5
+        assert!((recommend_min_size_mm_with(&meta(Some(1)), 100.0, &params) - 20.0).abs() < 1e-4);
+    }
+}
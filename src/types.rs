@@ -1,29 +1,175 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::fmt;
+use std::time::Duration;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Why a stress test passed or failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestOutcome {
+    Pass,
+    /// No symbol was detected at all.
+    NoGrid,
+    /// A symbol was detected but its data couldn't be read.
+    DecodeError,
+    /// A decoder panicked on the variant.
+    Panicked,
+    /// A non-decode check (such as `quiet_zone`) missed its threshold. Also
+    /// what a plain `false` deserializes to, since it doesn't say why.
+    Fail,
+}
+
+impl TestOutcome {
+    pub fn passed(self) -> bool {
+        self == Self::Pass
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::NoGrid => "no_grid",
+            Self::DecodeError => "decode_error",
+            Self::Panicked => "panicked",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+impl From<bool> for TestOutcome {
+    fn from(passed: bool) -> Self {
+        if passed { Self::Pass } else { Self::Fail }
+    }
+}
+
+impl<'de> Deserialize<'de> for TestOutcome {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = TestOutcome;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a boolean or a test outcome name")
+            }
+
+            fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<TestOutcome, E> {
+                Ok(v.into())
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<TestOutcome, E> {
+                [
+                    TestOutcome::Pass,
+                    TestOutcome::NoGrid,
+                    TestOutcome::DecodeError,
+                    TestOutcome::Panicked,
+                    TestOutcome::Fail,
+                ]
+                .into_iter()
+                .find(|o| o.as_str() == v)
+                .ok_or_else(|| E::unknown_variant(v, &["pass", "no_grid", "decode_error", "panicked", "fail"]))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct StressResults {
     #[serde(flatten)]
-    pub tests: BTreeMap<String, bool>,
-    #[serde(skip)]
+    pub tests: BTreeMap<String, TestOutcome>,
+    /// 0 when reading JSON written before the field was serialized.
+    #[serde(default)]
     pub contrast_ratio: f32,
+    #[serde(default = "default_wcag_contrast")]
+    pub wcag_contrast: f32,
+    /// Share of pixels at the dark or light level rather than in between; see
+    /// [`crate::scorer::measure_edge_sharpness`].
+    #[serde(default = "default_edge_sharpness")]
+    pub edge_sharpness: f32,
+    /// Serialize outcomes by name instead of as pass/fail booleans.
+    #[serde(skip)]
+    pub verbose_outcomes: bool,
 }
 
+fn default_wcag_contrast() -> f32 { 1.0 }
+fn default_edge_sharpness() -> f32 { 1.0 }
+
 impl Default for StressResults {
     fn default() -> Self {
         Self {
             tests: BTreeMap::new(),
             contrast_ratio: 0.0,
+            wcag_contrast: default_wcag_contrast(),
+            edge_sharpness: default_edge_sharpness(),
+            verbose_outcomes: false,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Hand-written so test outcomes stay `true`/`false` in the JSON unless
+// `verbose_outcomes` asks for the reason.
+impl Serialize for StressResults {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.tests.len() + 3))?;
+        for (name, outcome) in &self.tests {
+            if self.verbose_outcomes {
+                map.serialize_entry(name, outcome.as_str())?;
+            } else {
+                map.serialize_entry(name, &outcome.passed())?;
+            }
+        }
+        map.serialize_entry("contrast_ratio", &self.contrast_ratio)?;
+        map.serialize_entry("wcag_contrast", &self.wcag_contrast)?;
+        map.serialize_entry("edge_sharpness", &self.edge_sharpness)?;
+        map.end()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Weights {
     #[serde(flatten)]
     pub tests: BTreeMap<String, u32>,
     pub contrast_ratio: u32,
+    /// Continuous, like `contrast_ratio`. Off by default.
+    pub edge_sharpness: u32,
+}
+
+// Hand-written so a `[weights]` table only overrides the keys it lists and
+// everything else keeps its default weight.
+impl<'de> Deserialize<'de> for Weights {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Partial {
+            #[serde(flatten)]
+            tests: BTreeMap<String, u32>,
+            contrast_ratio: Option<u32>,
+            edge_sharpness: Option<u32>,
+        }
+
+        let partial = Partial::deserialize(deserializer)?;
+        let mut weights = Weights::default();
+        weights.tests.extend(partial.tests);
+        weights.contrast_ratio = partial.contrast_ratio.unwrap_or(weights.contrast_ratio);
+        weights.edge_sharpness = partial.edge_sharpness.unwrap_or(weights.edge_sharpness);
+        Ok(weights)
+    }
+}
+
+/// Weight of a `downscale_<N>px` test that has no explicit entry in `[weights]`.
+pub const DEFAULT_DOWNSCALE_SIZE_WEIGHT: u32 = 1;
+
+impl Weights {
+    /// Weight of `test`, falling back to [`DEFAULT_DOWNSCALE_SIZE_WEIGHT`] for
+    /// `downscale_<N>px` tests from `downscale_sizes` so they aren't dropped
+    /// from scoring. Other tests without an entry have no weight.
+    pub fn weight_of(&self, test: &str) -> Option<u32> {
+        self.tests.get(test).copied().or_else(|| {
+            let size = test.strip_prefix("downscale_")?.strip_suffix("px")?;
+            size.parse::<u32>().ok().map(|_| DEFAULT_DOWNSCALE_SIZE_WEIGHT)
+        })
+    }
 }
 
 impl Default for Weights {
@@ -43,6 +189,10 @@ impl Default for Weights {
             ("luminance_down".into(), 2),
             ("luminance_strict_up".into(), 1),
             ("luminance_strict_down".into(), 1),
+            ("gamma_up".into(), 1),
+            ("gamma_down".into(), 1),
+            ("gamma_strict_up".into(), 1),
+            ("gamma_strict_down".into(), 1),
             ("hue_up".into(), 1),
             ("hue_down".into(), 1),
             ("hue_strict_up".into(), 1),
@@ -51,20 +201,116 @@ impl Default for Weights {
             ("saturation_down".into(), 1),
             ("saturation_strict_up".into(), 1),
             ("saturation_strict_down".into(), 1),
+            ("lighting_gradient".into(), 2),
+            ("occlude_center".into(), 2),
+            ("erasure".into(), 2),
+            ("quiet_zone".into(), 2),
+            ("finder_integrity".into(), 2),
+            ("halftone".into(), 2),
+            ("scan_mid".into(), 3),
+            ("scan_far".into(), 5),
         ]);
         Self {
             tests,
             contrast_ratio: 70,
+            edge_sharpness: 0,
         }
     }
 }
 
+/// Barcode symbologies the decoders may look for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolFormat {
+    #[default]
+    Qr,
+    DataMatrix,
+    Aztec,
+    Pdf417,
+}
+
+/// Which luminance cluster holds the modules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Polarity {
+    /// Whichever cluster covers fewer pixels.
+    #[default]
+    Auto,
+    DarkOnLight,
+    LightOnDark,
+}
+
+/// Which frame of an animated input `validate` scores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FramePolicy {
+    /// The first frame that decodes.
+    #[default]
+    FirstDecodable,
+    /// Every frame that decodes, keeping the highest score.
+    BestScore,
+}
+
+/// Decoder backends, tried in the order listed in `TestConfig::decoders`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecoderKind {
+    /// rxing with its local-threshold binarizer.
+    RxingHybrid,
+    /// rxing with a single global threshold.
+    RxingGlobalHistogram,
+    /// rqrr on the image as-is. QR only.
+    Rqrr,
+    /// rqrr on the inverted image, for light-on-dark codes. QR only.
+    RqrrInverted,
+}
+
+/// Minimum score for each letter grade; anything below `d` is an F.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GradeThresholds {
+    pub a: u8,
+    pub b: u8,
+    pub c: u8,
+    pub d: u8,
+}
+
+impl Default for GradeThresholds {
+    fn default() -> Self {
+        Self {
+            a: 80,
+            b: 60,
+            c: 40,
+            d: 20,
+        }
+    }
+}
+
+/// `Option<Duration>` as fractional seconds.
+mod duration_secs {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(value: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        value.map(|d| d.as_secs_f64()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        Option::<f64>::deserialize(deserializer)?
+            .map(|secs| Duration::try_from_secs_f64(secs).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestConfig {
     #[serde(default = "default_render_size")]
     pub render_size: u32,
     #[serde(skip)]
     pub native_size: Option<u32>,
+    /// Exact pixel sizes to downscale to, replacing the `native_size` multiples.
+    #[serde(default)]
+    pub downscale_sizes: Option<Vec<u32>>,
     #[serde(default = "default_blur_light_sigma")]
     pub blur_light_sigma: f32,
     #[serde(default = "default_blur_heavy_sigma")]
@@ -77,6 +323,11 @@ pub struct TestConfig {
     pub luminance: i32,
     #[serde(default = "default_luminance_strict")]
     pub luminance_strict: i32,
+    /// Gamma exponents; each is tested as-is and inverted.
+    #[serde(default = "default_gamma")]
+    pub gamma: f32,
+    #[serde(default = "default_gamma_strict")]
+    pub gamma_strict: f32,
     #[serde(default = "default_hue")]
     pub hue: f32,
     #[serde(default = "default_hue_strict")]
@@ -85,6 +336,80 @@ pub struct TestConfig {
     pub saturation: f32,
     #[serde(default = "default_saturation_strict")]
     pub saturation_strict: f32,
+    #[serde(default = "default_lighting_gradient")]
+    pub lighting_gradient: f32,
+    #[serde(default = "default_occlusion_fraction")]
+    pub occlusion_fraction: f32,
+    #[serde(default)]
+    pub occlusion_color: [u8; 3],
+    /// Share of the symbol area the `erasure` test blanks in small patches.
+    /// Whether a code survives this depends mostly on its error correction
+    /// level, so read the result alongside `error_correction`.
+    #[serde(default = "default_erasure_fraction")]
+    pub erasure_fraction: f32,
+    /// Seed for where the `erasure` patches land; the same seed always
+    /// blanks the same patches.
+    #[serde(default = "default_erasure_seed")]
+    pub erasure_seed: u64,
+    /// Pixels per Bayer matrix entry in the `halftone` test.
+    #[serde(default = "default_halftone_cell")]
+    pub halftone_cell: u32,
+    /// Longest side, in pixels, of the `scan_far` / `scan_mid` distance simulations.
+    #[serde(default = "default_scan_far_size")]
+    pub scan_far_size: u32,
+    #[serde(default = "default_scan_mid_size")]
+    pub scan_mid_size: u32,
+    /// Blur sigma added per unit of downscale factor in the distance simulations.
+    #[serde(default = "default_scan_blur_per_factor")]
+    pub scan_blur_per_factor: f32,
+    #[serde(default = "default_min_quiet_zone_modules")]
+    pub min_quiet_zone_modules: f32,
+    /// Largest relative finder run deviation `finder_integrity` accepts.
+    #[serde(default = "default_finder_tolerance")]
+    pub finder_tolerance: f32,
+    #[serde(default)]
+    pub max_dimension: Option<u32>,
+    /// Give up with `QrScoreError::TimedOut` after this long. Written in
+    /// TOML as seconds, e.g. `timeout = 2.5`.
+    #[serde(default, with = "duration_secs")]
+    pub timeout: Option<Duration>,
+    /// Region to score as `(x, y, width, height)`, applied right after loading.
+    #[serde(default)]
+    pub crop: Option<(u32, u32, u32, u32)>,
+    #[serde(default = "default_respect_exif")]
+    pub respect_exif: bool,
+    #[serde(default)]
+    pub verbose_outcomes: bool,
+    #[serde(default)]
+    pub collect_diagnostics: bool,
+    /// Re-render an SVG that doesn't decode at twice the size and score that
+    /// instead.
+    #[serde(default)]
+    pub upscale_retry: bool,
+    #[serde(default = "default_formats")]
+    pub formats: Vec<SymbolFormat>,
+    #[serde(default = "default_decoders")]
+    pub decoders: Vec<DecoderKind>,
+    #[serde(default)]
+    pub frame_policy: FramePolicy,
+    /// Read at most this many frames of an animated input (default 100).
+    /// Later frames aren't decoded or scored.
+    #[serde(default)]
+    pub max_frames: Option<usize>,
+    /// Which cluster `contrast_ratio` and the hue/saturation tests treat as
+    /// the modules.
+    #[serde(default)]
+    pub polarity: Polarity,
+    #[serde(default)]
+    pub grades: GradeThresholds,
+    /// Contrast ratio (0-1) below which the grade is capped at
+    /// `contrast_floor_grade`, however well the stress tests did. The score
+    /// is left as is.
+    #[serde(default)]
+    pub min_contrast_floor: Option<f32>,
+    /// Best grade a result under `min_contrast_floor` can get.
+    #[serde(default = "default_contrast_floor_grade")]
+    pub contrast_floor_grade: IsoGrade,
     #[serde(default)]
     pub weights: Weights,
 }
@@ -96,43 +421,408 @@ fn default_contrast() -> f32 { 30.0 }
 fn default_contrast_strict() -> f32 { 50.0 }
 fn default_luminance() -> i32 { 20 }
 fn default_luminance_strict() -> i32 { 40 }
+fn default_gamma() -> f32 { 1.5 }
+fn default_gamma_strict() -> f32 { 2.2 }
 fn default_hue() -> f32 { 45.0 }
 fn default_hue_strict() -> f32 { 90.0 }
 fn default_saturation() -> f32 { 30.0 }
 fn default_saturation_strict() -> f32 { 50.0 }
+fn default_lighting_gradient() -> f32 { 0.5 }
+fn default_occlusion_fraction() -> f32 { 0.15 }
+fn default_erasure_fraction() -> f32 { 0.05 }
+fn default_erasure_seed() -> u64 { 1 }
+fn default_halftone_cell() -> u32 { 2 }
+fn default_finder_tolerance() -> f32 { 0.3 }
+fn default_scan_far_size() -> u32 { 100 }
+fn default_scan_mid_size() -> u32 { 200 }
+fn default_scan_blur_per_factor() -> f32 { 0.25 }
+fn default_min_quiet_zone_modules() -> f32 { 4.0 }
+fn default_formats() -> Vec<SymbolFormat> { vec![SymbolFormat::Qr] }
+fn default_decoders() -> Vec<DecoderKind> {
+    vec![
+        DecoderKind::RxingHybrid,
+        DecoderKind::RxingGlobalHistogram,
+        DecoderKind::Rqrr,
+        DecoderKind::RqrrInverted,
+    ]
+}
+fn default_respect_exif() -> bool { true }
+fn default_contrast_floor_grade() -> IsoGrade { IsoGrade::F }
 
 impl Default for TestConfig {
     fn default() -> Self {
         Self {
             render_size: default_render_size(),
             native_size: None,
+            downscale_sizes: None,
             blur_light_sigma: default_blur_light_sigma(),
             blur_heavy_sigma: default_blur_heavy_sigma(),
             contrast: default_contrast(),
             contrast_strict: default_contrast_strict(),
             luminance: default_luminance(),
             luminance_strict: default_luminance_strict(),
+            gamma: default_gamma(),
+            gamma_strict: default_gamma_strict(),
             hue: default_hue(),
             hue_strict: default_hue_strict(),
             saturation: default_saturation(),
             saturation_strict: default_saturation_strict(),
+            lighting_gradient: default_lighting_gradient(),
+            occlusion_fraction: default_occlusion_fraction(),
+            occlusion_color: [0, 0, 0],
+            erasure_fraction: default_erasure_fraction(),
+            erasure_seed: default_erasure_seed(),
+            halftone_cell: default_halftone_cell(),
+            scan_far_size: default_scan_far_size(),
+            scan_mid_size: default_scan_mid_size(),
+            scan_blur_per_factor: default_scan_blur_per_factor(),
+            min_quiet_zone_modules: default_min_quiet_zone_modules(),
+            finder_tolerance: default_finder_tolerance(),
+            max_dimension: None,
+            timeout: None,
+            crop: None,
+            respect_exif: default_respect_exif(),
+            verbose_outcomes: false,
+            collect_diagnostics: false,
+            upscale_retry: false,
+            formats: default_formats(),
+            decoders: default_decoders(),
+            frame_policy: FramePolicy::default(),
+            max_frames: None,
+            polarity: Polarity::default(),
+            grades: GradeThresholds::default(),
+            min_contrast_floor: None,
+            contrast_floor_grade: default_contrast_floor_grade(),
             weights: Weights::default(),
         }
     }
 }
 
+impl TestConfig {
+    /// Start a [`TestConfigBuilder`] from the default configuration.
+    ///
+    /// ```
+    /// let config = qr_score::TestConfig::builder().blur_heavy_sigma(3.0).build();
+    /// assert_eq!(config.blur_heavy_sigma, 3.0);
+    /// ```
+    pub fn builder() -> TestConfigBuilder {
+        TestConfigBuilder::default()
+    }
+}
+
+/// Fluent alternative to struct-updating a [`TestConfig`] off `Default`.
+#[derive(Debug, Clone, Default)]
+pub struct TestConfigBuilder {
+    config: TestConfig,
+}
+
+macro_rules! builder_setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            pub fn $field(mut self, value: $ty) -> Self {
+                self.config.$field = value;
+                self
+            }
+        )*
+    };
+}
+
+impl TestConfigBuilder {
+    builder_setters! {
+        render_size: u32,
+        blur_light_sigma: f32,
+        blur_heavy_sigma: f32,
+        contrast: f32,
+        contrast_strict: f32,
+        luminance: i32,
+        luminance_strict: i32,
+        gamma: f32,
+        gamma_strict: f32,
+        hue: f32,
+        hue_strict: f32,
+        saturation: f32,
+        saturation_strict: f32,
+        lighting_gradient: f32,
+        occlusion_fraction: f32,
+        occlusion_color: [u8; 3],
+        erasure_fraction: f32,
+        erasure_seed: u64,
+        halftone_cell: u32,
+        scan_far_size: u32,
+        scan_mid_size: u32,
+        scan_blur_per_factor: f32,
+        min_quiet_zone_modules: f32,
+        finder_tolerance: f32,
+        respect_exif: bool,
+        verbose_outcomes: bool,
+        collect_diagnostics: bool,
+        upscale_retry: bool,
+        formats: Vec<SymbolFormat>,
+        decoders: Vec<DecoderKind>,
+        frame_policy: FramePolicy,
+        polarity: Polarity,
+        grades: GradeThresholds,
+        contrast_floor_grade: IsoGrade,
+        weights: Weights,
+    }
+
+    pub fn native_size(mut self, size: u32) -> Self {
+        self.config.native_size = Some(size);
+        self
+    }
+
+    pub fn downscale_sizes(mut self, sizes: Vec<u32>) -> Self {
+        self.config.downscale_sizes = Some(sizes);
+        self
+    }
+
+    pub fn max_dimension(mut self, max: u32) -> Self {
+        self.config.max_dimension = Some(max);
+        self
+    }
+
+    pub fn max_frames(mut self, max: usize) -> Self {
+        self.config.max_frames = Some(max);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = Some(timeout);
+        self
+    }
+
+    pub fn crop(mut self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        self.config.crop = Some((x, y, width, height));
+        self
+    }
+
+    pub fn min_contrast_floor(mut self, floor: f32) -> Self {
+        self.config.min_contrast_floor = Some(floor);
+        self
+    }
+
+    /// Set the weight of a single stress test, adding it if not already present.
+    pub fn weight(mut self, name: impl Into<String>, weight: u32) -> Self {
+        self.config.weights.tests.insert(name.into(), weight);
+        self
+    }
+
+    pub fn contrast_ratio_weight(mut self, weight: u32) -> Self {
+        self.config.weights.contrast_ratio = weight;
+        self
+    }
+
+    pub fn edge_sharpness_weight(mut self, weight: u32) -> Self {
+        self.config.weights.edge_sharpness = weight;
+        self
+    }
+
+    pub fn build(self) -> TestConfig {
+        self.config
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationResult {
     pub score: u8,
+    pub grade: String,
     pub decodable: bool,
     pub content: Option<String>,
     pub metadata: Option<QrMetadata>,
     pub stress_results: StressResults,
+    /// Index of the animation frame that was scored; 0 for still images.
+    #[serde(default)]
+    pub frame: usize,
+    /// Phase timings, present only when `collect_diagnostics` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<Diagnostics>,
+    /// What the source SVG contains besides vector paths; `None` when
+    /// scoring an already-raster image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub svg: Option<SvgDiagnostics>,
+}
+
+/// Content in an SVG that makes its score less trustworthy as a measure of
+/// the vector design.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SvgDiagnostics {
+    /// An embedded PNG, JPEG, GIF or WebP, so part of what's scored is a
+    /// bitmap rasterized at its own resolution.
+    pub raster_image: bool,
+    /// A `<text>` element. It renders differently depending on the fonts
+    /// installed, and is dropped when none match.
+    pub text: bool,
+    /// An `href` to a file or URL outside the document, which isn't loaded.
+    pub external_reference: bool,
+}
+
+impl SvgDiagnostics {
+    /// Nothing but vector shapes.
+    pub fn is_pure_vector(&self) -> bool {
+        !(self.raster_image || self.text || self.external_reference)
+    }
+}
+
+/// Wall-clock time spent in each scoring phase, in milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostics {
+    /// SVG rasterization; `None` when scoring an already-raster image.
+    pub render_ms: Option<f64>,
+    /// Decoding the original, after it's loaded and cropped.
+    pub decode_ms: f64,
+    /// Running every stress test, in parallel.
+    pub stress_ms: f64,
+    /// Render sizes used; `None` when scoring an already-raster image.
+    #[serde(default)]
+    pub render: Option<RenderInfo>,
+    /// The first render didn't decode and the result comes from the
+    /// `upscale_retry` render at twice the size.
+    #[serde(default)]
+    pub upscale_retry: bool,
+}
+
+/// How the SVG rasterization size was chosen, in pixels along the longest side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenderInfo {
+    /// `TestConfig::render_size`.
+    pub configured: u32,
+    /// The SVG's intrinsic size.
+    pub native: u32,
+    /// The size actually rendered at: the larger of the two.
+    pub effective: u32,
+}
+
+impl RenderInfo {
+    /// Whether the SVG's native size overrode the configured render size.
+    pub fn overridden(&self) -> bool {
+        self.effective != self.configured
+    }
+}
+
+/// How a candidate render scored against a baseline. An input that couldn't
+/// be scored counts as score 0 with every stress test failed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompareResult {
+    pub baseline_score: u8,
+    pub candidate_score: u8,
+    /// `candidate_score - baseline_score`.
+    pub score_delta: i16,
+    /// Candidate minus baseline contrast ratio (0-1 scale).
+    pub contrast_ratio_delta: f32,
+    /// Tests that failed on the baseline and pass on the candidate.
+    pub newly_passing: Vec<String>,
+    /// Tests that passed on the baseline and fail on the candidate.
+    pub regressed: Vec<String>,
+}
+
+impl CompareResult {
+    pub fn between(baseline: Option<&ValidationResult>, candidate: Option<&ValidationResult>) -> Self {
+        let score = |r: Option<&ValidationResult>| r.map_or(0, |r| r.score);
+        let contrast = |r: Option<&ValidationResult>| r.map_or(0.0, |r| r.stress_results.contrast_ratio);
+        let passed = |r: Option<&ValidationResult>, name: &str| {
+            r.and_then(|r| r.stress_results.tests.get(name)).is_some_and(|o| o.passed())
+        };
+
+        let names: std::collections::BTreeSet<&String> = [baseline, candidate]
+            .into_iter()
+            .flatten()
+            .flat_map(|r| r.stress_results.tests.keys())
+            .collect();
+        let (mut newly_passing, mut regressed) = (Vec::new(), Vec::new());
+        for name in names {
+            match (passed(baseline, name), passed(candidate, name)) {
+                (false, true) => newly_passing.push(name.clone()),
+                (true, false) => regressed.push(name.clone()),
+                _ => {}
+            }
+        }
+
+        Self {
+            baseline_score: score(baseline),
+            candidate_score: score(candidate),
+            score_delta: score(candidate) as i16 - score(baseline) as i16,
+            contrast_ratio_delta: contrast(candidate) - contrast(baseline),
+            newly_passing,
+            regressed,
+        }
+    }
+
+    pub fn has_regressions(&self) -> bool {
+        !self.regressed.is_empty()
+    }
+}
+
+/// The JSON shape the CLI prints for a scored input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreReport {
+    pub score: u8,
+    pub grade: String,
+    pub decodable: bool,
+    pub content: Option<String>,
+    pub results: StressResults,
+    /// `results.contrast_ratio` scaled to 0-100 and rounded.
+    pub contrast_ratio: u8,
+    /// `results.contrast_ratio` unrounded, on the 0-1 scale.
+    pub contrast_ratio_f32: f32,
+    pub wcag_contrast: f32,
+    /// `"L"`, `"M"`, `"Q"` or `"H"`.
+    pub error_correction: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub svg: Option<SvgDiagnostics>,
+}
+
+impl From<ValidationResult> for ScoreReport {
+    fn from(result: ValidationResult) -> Self {
+        let sr = result.stress_results;
+        Self {
+            score: result.score,
+            grade: result.grade,
+            decodable: result.decodable,
+            content: result.content,
+            contrast_ratio: (sr.contrast_ratio * 100.0).round() as u8,
+            contrast_ratio_f32: sr.contrast_ratio,
+            wcag_contrast: sr.wcag_contrast,
+            error_correction: result.metadata.map(|m| m.error_correction.to_string()),
+            svg: result.svg,
+            results: sr,
+        }
+    }
+}
+
+/// The JSON shape the CLI prints when an input couldn't be scored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorReport {
+    pub score: u8,
+    pub grade: String,
+    pub decodable: bool,
+    pub error: String,
+}
+
+impl ErrorReport {
+    /// Score 0, grade F.
+    pub fn new(error: impl Into<String>) -> Self {
+        Self {
+            score: 0,
+            grade: "F".to_string(),
+            decodable: false,
+            error: error.into(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QrMetadata {
     pub error_correction: ErrorCorrectionLevel,
+    /// QR version (1-40), when the symbol's grid could be measured.
+    #[serde(default)]
+    pub version: Option<u8>,
+}
+
+impl QrMetadata {
+    /// Modules per side, `17 + 4 * version`.
+    pub fn modules(&self) -> Option<u32> {
+        self.version.map(|v| 17 + 4 * v as u32)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -155,10 +845,161 @@ impl fmt::Display for ErrorCorrectionLevel {
     }
 }
 
+/// A symbol's modules as sampled from the image, row-major, `true` for dark.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BitMatrix {
+    /// Modules per side.
+    pub size: usize,
+    /// `size * size` modules, row by row from the top-left.
+    pub bits: Vec<bool>,
+}
+
+impl BitMatrix {
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        self.bits[row * self.size + col]
+    }
+}
+
+/// One finder pattern measured along its center lines.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FinderPattern {
+    /// Center in image pixels.
+    pub center: (f32, f32),
+    /// Run widths left to right, scaled to sum to 7 modules; ideal is 1:1:3:1:1.
+    pub horizontal: [f32; 5],
+    /// Run widths top to bottom, scaled the same way.
+    pub vertical: [f32; 5],
+    /// Largest relative difference of any run from its ideal width.
+    pub deviation: f32,
+}
+
+/// How cleanly a QR's finder patterns match the ideal 1:1:3:1:1 ratio.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FinderReport {
+    /// Top-left, top-right and bottom-left finders; empty if no grid was found.
+    pub finders: Vec<FinderPattern>,
+}
+
+impl FinderReport {
+    pub fn worst_deviation(&self) -> Option<f32> {
+        self.finders.iter().map(|f| f.deviation).reduce(f32::max)
+    }
+
+    /// All three finders were found and none deviates by more than `tolerance`.
+    pub fn passes(&self, tolerance: f32) -> bool {
+        self.finders.len() == 3 && self.worst_deviation().is_some_and(|d| d <= tolerance)
+    }
+}
+
+/// Light margin on each side of the symbol, in module widths.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuietZoneReport {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+    /// Narrowest of the four sides; the QR spec recommends at least 4.
+    pub min: f32,
+    /// Detected module pitch in pixels.
+    pub module_size: f32,
+}
+
+/// An ISO/IEC 15415 parameter grade, from A (4.0) down to F (0.0). Also
+/// the letter scale `contrast_floor_grade` caps the overall grade on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum IsoGrade {
+    F,
+    D,
+    C,
+    B,
+    A,
+}
+
+impl IsoGrade {
+    /// Numeric grade as used in the standard: A = 4.0 through F = 0.0.
+    pub fn value(self) -> f32 {
+        match self {
+            Self::A => 4.0,
+            Self::B => 3.0,
+            Self::C => 2.0,
+            Self::D => 1.0,
+            Self::F => 0.0,
+        }
+    }
+
+    pub fn letter(self) -> &'static str {
+        match self {
+            Self::A => "A",
+            Self::B => "B",
+            Self::C => "C",
+            Self::D => "D",
+            Self::F => "F",
+        }
+    }
+}
+
+/// ISO/IEC 15415-style print quality parameters measured at the module
+/// centers. Reflectances are on a 0-1 scale.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Iso15415Report {
+    /// Whether the symbol decodes at all.
+    pub decode: IsoGrade,
+    /// `max_reflectance - min_reflectance`.
+    pub symbol_contrast: f32,
+    pub symbol_contrast_grade: IsoGrade,
+    pub min_reflectance: f32,
+    pub max_reflectance: f32,
+    /// A if `min_reflectance` is at most half of `max_reflectance`, else F.
+    pub min_reflectance_grade: IsoGrade,
+    /// Lowest per-module modulation, `2 * |R - GT| / SC`.
+    pub modulation: f32,
+    pub modulation_grade: IsoGrade,
+    /// The lowest of the parameter grades.
+    pub overall: IsoGrade,
+}
+
+/// How serious a [`ConfigIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    /// Scoring works, but probably not as intended.
+    Warning,
+    /// `validate` would reject the config, or scores would be meaningless.
+    Error,
+}
+
+/// A problem [`crate::lint_config`] found in a [`TestConfig`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigIssue {
+    pub severity: IssueSeverity,
+    /// Config key the issue is about, e.g. `contrast_strict` or `weights.blur_light`.
+    pub field: String,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    pub fn is_error(&self) -> bool {
+        self.severity == IssueSeverity::Error
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DecodeResult {
     pub content: String,
+    #[serde(default)]
+    pub format: SymbolFormat,
     pub metadata: Option<QrMetadata>,
+    /// Location of the decoded QR as top-left, top-right, bottom-right,
+    /// bottom-left, in the input image's pixel space.
+    ///
+    /// These are the outer corners of the symbol, except when rxing's legacy
+    /// reader is the one that decodes: it only reports the three finder pattern
+    /// centers, so the corners sit 3.5 modules inside the symbol edge.
+    #[serde(default)]
+    pub corners: Option<[(f32, f32); 4]>,
+    /// Index of the animation frame this was decoded from; 0 for still images.
+    #[serde(default)]
+    pub frame: usize,
 }
 
 #[cfg(test)]
@@ -176,13 +1017,22 @@ mod tests {
         assert_eq!(config.hue, 45.0);
         assert_eq!(config.hue_strict, 90.0);
         assert!(config.native_size.is_none());
+        assert!(config.max_dimension.is_none());
+        assert!(config.max_frames.is_none());
+        assert_eq!(config.occlusion_fraction, 0.15);
+        assert_eq!(config.occlusion_color, [0, 0, 0]);
+        assert_eq!(config.formats, vec![SymbolFormat::Qr]);
+        assert!(config.respect_exif);
     }
 
     #[test]
-    fn weights_default_sum_to_100() {
+    fn weights_default_extend_the_original_100() {
         let w = Weights::default();
         let sum: u32 = w.tests.values().sum::<u32>() + w.contrast_ratio;
-        assert_eq!(sum, 100);
+        // Tests added after the original 100 extend the total rather than
+        // taking weight from contrast
+        assert_eq!(w.contrast_ratio, 70);
+        assert_eq!(sum, 124);
     }
 
     #[test]
@@ -190,6 +1040,66 @@ mod tests {
         let sr = StressResults::default();
         assert!(sr.tests.is_empty());
         assert_eq!(sr.contrast_ratio, 0.0);
+        assert_eq!(sr.wcag_contrast, 1.0);
+    }
+
+    #[test]
+    fn stress_results_serde_roundtrip_keeps_contrast_ratio() {
+        let mut sr = StressResults::default();
+        sr.tests.insert("blur_light".into(), TestOutcome::Pass);
+        sr.contrast_ratio = 0.8125;
+        let json = serde_json::to_string(&sr).unwrap();
+        let back: StressResults = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.contrast_ratio, 0.8125);
+        assert_eq!(back.edge_sharpness, 1.0);
+        assert_eq!(back.tests.len(), 1);
+        assert_eq!(back.tests.get("blur_light"), Some(&TestOutcome::Pass));
+
+        // Older output had no contrast_ratio key
+        let back: StressResults = serde_json::from_str(r#"{"blur_light":true}"#).unwrap();
+        assert_eq!(back.contrast_ratio, 0.0);
+        assert_eq!(back.tests.len(), 1);
+    }
+
+    #[test]
+    fn stress_results_serialize_outcomes_as_bool_by_default() {
+        let mut sr = StressResults::default();
+        sr.tests.insert("blur_light".into(), TestOutcome::Pass);
+        sr.tests.insert("blur_heavy".into(), TestOutcome::NoGrid);
+        sr.tests.insert("downscale_1x".into(), TestOutcome::DecodeError);
+        let json: serde_json::Value = serde_json::to_value(&sr).unwrap();
+        assert_eq!(json["blur_light"], true);
+        assert_eq!(json["blur_heavy"], false);
+        assert_eq!(json["downscale_1x"], false);
+
+        let back: StressResults = serde_json::from_value(json).unwrap();
+        assert_eq!(back.tests["blur_light"], TestOutcome::Pass);
+        assert_eq!(back.tests["blur_heavy"], TestOutcome::Fail);
+    }
+
+    #[test]
+    fn stress_results_verbose_outcomes_roundtrip() {
+        let mut sr = StressResults { verbose_outcomes: true, ..StressResults::default() };
+        sr.tests.insert("blur_light".into(), TestOutcome::Pass);
+        sr.tests.insert("blur_heavy".into(), TestOutcome::NoGrid);
+        sr.tests.insert("downscale_1x".into(), TestOutcome::Panicked);
+        let json: serde_json::Value = serde_json::to_value(&sr).unwrap();
+        assert_eq!(json["blur_light"], "pass");
+        assert_eq!(json["blur_heavy"], "no_grid");
+        assert_eq!(json["downscale_1x"], "panicked");
+
+        let back: StressResults = serde_json::from_value(json).unwrap();
+        assert_eq!(back.tests, sr.tests);
+        assert!(serde_json::from_str::<StressResults>(r#"{"blur_light":"bogus","contrast_ratio":0.5}"#).is_err());
+    }
+
+    #[test]
+    fn metadata_modules_from_version() {
+        let meta = QrMetadata { error_correction: ErrorCorrectionLevel::M, version: Some(2) };
+        assert_eq!(meta.modules(), Some(25));
+        let meta = QrMetadata { version: Some(40), ..meta };
+        assert_eq!(meta.modules(), Some(177));
+        assert_eq!(QrMetadata { version: None, ..meta }.modules(), None);
     }
 
     #[test]
@@ -208,13 +1118,53 @@ mod tests {
             "blur_light", "blur_heavy",
             "contrast_up", "contrast_down", "contrast_strict_up", "contrast_strict_down",
             "luminance_up", "luminance_down", "luminance_strict_up", "luminance_strict_down",
+            "gamma_up", "gamma_down", "gamma_strict_up", "gamma_strict_down",
             "hue_up", "hue_down", "hue_strict_up", "hue_strict_down",
             "saturation_up", "saturation_down", "saturation_strict_up", "saturation_strict_down",
+            "lighting_gradient", "occlude_center", "erasure", "quiet_zone", "finder_integrity",
+            "halftone", "scan_mid", "scan_far",
         ];
         for key in expected {
             assert!(w.tests.contains_key(key), "missing weight key: {}", key);
         }
-        assert_eq!(w.tests.len(), 22);
+        assert_eq!(w.tests.len(), 34);
+    }
+
+    #[test]
+    fn weight_of_falls_back_for_downscale_sizes() {
+        let mut w = Weights::default();
+        assert_eq!(w.weight_of("downscale_2x"), Some(2));
+        assert_eq!(w.weight_of("downscale_60px"), Some(DEFAULT_DOWNSCALE_SIZE_WEIGHT));
+        assert_eq!(w.weight_of("downscale_bigpx"), None);
+        assert_eq!(w.weight_of("not_a_test"), None);
+
+        w.tests.insert("downscale_60px".into(), 4);
+        assert_eq!(w.weight_of("downscale_60px"), Some(4));
+    }
+
+    #[test]
+    fn downscale_sizes_from_toml() {
+        let config: TestConfig = toml::from_str("downscale_sizes = [60, 90]").unwrap();
+        assert_eq!(config.downscale_sizes, Some(vec![60, 90]));
+        assert!(TestConfig::default().downscale_sizes.is_none());
+    }
+
+    #[test]
+    fn builder_overrides_selected_fields() {
+        let config = TestConfig::builder()
+            .blur_light_sigma(0.5)
+            .contrast(10.0)
+            .native_size(64)
+            .weight("blur_light", 5)
+            .contrast_ratio_weight(60)
+            .build();
+        assert_eq!(config.blur_light_sigma, 0.5);
+        assert_eq!(config.contrast, 10.0);
+        assert_eq!(config.native_size, Some(64));
+        assert_eq!(config.weights.tests.get("blur_light"), Some(&5));
+        assert_eq!(config.weights.contrast_ratio, 60);
+        assert_eq!(config.blur_heavy_sigma, 2.0);
+        assert_eq!(config.weights.tests.len(), 34);
     }
 
     #[test]
@@ -230,5 +1180,158 @@ mod tests {
         assert_eq!(config.contrast_strict, 60.0);
         assert_eq!(config.luminance, 20);
         assert_eq!(config.hue, 45.0);
+        assert_eq!(config.grades, GradeThresholds::default());
+    }
+
+    #[test]
+    fn partial_weights_merge_over_defaults() {
+        let config: TestConfig = toml::from_str("[weights]\nblur_light = 7\ncontrast_ratio = 70\n").unwrap();
+        let defaults = Weights::default();
+        assert_eq!(config.weights.tests.get("blur_light"), Some(&7));
+        assert_eq!(config.weights.tests.get("scan_far"), defaults.tests.get("scan_far"));
+        assert_eq!(config.weights.tests.len(), defaults.tests.len());
+        assert_eq!(config.weights.contrast_ratio, 70);
+        assert_eq!(config.weights.edge_sharpness, 0);
+    }
+
+    #[test]
+    fn formats_from_toml() {
+        let config: TestConfig = toml::from_str(r#"formats = ["qr", "data_matrix", "pdf417"]"#).unwrap();
+        assert_eq!(
+            config.formats,
+            vec![SymbolFormat::Qr, SymbolFormat::DataMatrix, SymbolFormat::Pdf417]
+        );
+    }
+
+    fn result_with(score: u8, contrast_ratio: f32, tests: &[(&str, TestOutcome)]) -> ValidationResult {
+        ValidationResult {
+            score,
+            grade: "A".into(),
+            decodable: true,
+            content: None,
+            metadata: None,
+            stress_results: StressResults {
+                tests: tests.iter().map(|&(n, o)| (n.to_string(), o)).collect(),
+                contrast_ratio,
+                ..StressResults::default()
+            },
+            frame: 0,
+            diagnostics: None,
+            svg: None,
+        }
+    }
+
+    #[test]
+    fn score_report_from_validation_result() {
+        let mut result = result_with(87, 0.9412, &[("blur_light", TestOutcome::Pass)]);
+        result.metadata = Some(QrMetadata { error_correction: ErrorCorrectionLevel::Q, version: Some(2) });
+        let report = ScoreReport::from(result);
+        assert_eq!(report.score, 87);
+        assert_eq!(report.contrast_ratio, 94);
+        assert_eq!(report.contrast_ratio_f32, 0.9412);
+        assert_eq!(report.error_correction.as_deref(), Some("Q"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["results"]["blur_light"], serde_json::json!(true));
+        assert_eq!(json["error_correction"], "Q");
+        let back: ScoreReport = serde_json::from_value(json).unwrap();
+        assert_eq!(back.results.tests.get("blur_light"), Some(&TestOutcome::Pass));
+    }
+
+    #[test]
+    fn error_report_is_score_zero_grade_f() {
+        let json = serde_json::to_string(&ErrorReport::new("No QR code found")).unwrap();
+        assert_eq!(json, r#"{"score":0,"grade":"F","decodable":false,"error":"No QR code found"}"#);
+    }
+
+    #[test]
+    fn compare_lists_regressions_and_improvements() {
+        let baseline = result_with(80, 0.9, &[
+            ("blur_light", TestOutcome::Pass),
+            ("blur_heavy", TestOutcome::NoGrid),
+            ("scan_far", TestOutcome::Pass),
+        ]);
+        let candidate = result_with(75, 0.8, &[
+            ("blur_light", TestOutcome::Pass),
+            ("blur_heavy", TestOutcome::Pass),
+            ("scan_far", TestOutcome::DecodeError),
+        ]);
+        let diff = CompareResult::between(Some(&baseline), Some(&candidate));
+        assert_eq!(diff.score_delta, -5);
+        assert!((diff.contrast_ratio_delta + 0.1).abs() < 1e-6);
+        assert_eq!(diff.newly_passing, vec!["blur_heavy"]);
+        assert_eq!(diff.regressed, vec!["scan_far"]);
+        assert!(diff.has_regressions());
+    }
+
+    #[test]
+    fn compare_unscorable_candidate_regresses_everything() {
+        let baseline = result_with(90, 0.9, &[("blur_light", TestOutcome::Pass), ("hue_up", TestOutcome::Pass)]);
+        let diff = CompareResult::between(Some(&baseline), None);
+        assert_eq!(diff.candidate_score, 0);
+        assert_eq!(diff.score_delta, -90);
+        assert_eq!(diff.regressed, vec!["blur_light", "hue_up"]);
+    }
+
+    #[test]
+    fn timeout_from_toml_seconds() {
+        let config: TestConfig = toml::from_str("timeout = 2.5").unwrap();
+        assert_eq!(config.timeout, Some(Duration::from_millis(2500)));
+        let config: TestConfig = toml::from_str("timeout = 3").unwrap();
+        assert_eq!(config.timeout, Some(Duration::from_secs(3)));
+        assert!(toml::from_str::<TestConfig>("timeout = -1.0").is_err());
+        assert!(TestConfig::default().timeout.is_none());
+    }
+
+    #[test]
+    fn polarity_from_toml() {
+        let config: TestConfig = toml::from_str(r#"polarity = "light_on_dark""#).unwrap();
+        assert_eq!(config.polarity, Polarity::LightOnDark);
+        assert_eq!(TestConfig::default().polarity, Polarity::Auto);
+    }
+
+    #[test]
+    fn frame_policy_from_toml() {
+        let config: TestConfig = toml::from_str(r#"frame_policy = "best_score""#).unwrap();
+        assert_eq!(config.frame_policy, FramePolicy::BestScore);
+        assert_eq!(TestConfig::default().frame_policy, FramePolicy::FirstDecodable);
+    }
+
+    #[test]
+    fn crop_from_toml() {
+        let config: TestConfig = toml::from_str("crop = [10, 20, 300, 300]").unwrap();
+        assert_eq!(config.crop, Some((10, 20, 300, 300)));
+        assert_eq!(TestConfig::builder().crop(1, 2, 3, 4).build().crop, Some((1, 2, 3, 4)));
+    }
+
+    #[test]
+    fn decoders_from_toml() {
+        let config: TestConfig = toml::from_str(r#"decoders = ["rqrr", "rxing_hybrid"]"#).unwrap();
+        assert_eq!(config.decoders, vec![DecoderKind::Rqrr, DecoderKind::RxingHybrid]);
+        assert_eq!(TestConfig::default().decoders.len(), 4);
+    }
+
+    #[test]
+    fn grade_thresholds_from_toml() {
+        let toml_str = r#"
+            [grades]
+            a = 90
+            b = 75
+        "#;
+        let config: TestConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.grades.a, 90);
+        assert_eq!(config.grades.b, 75);
+        assert_eq!(config.grades.c, 40);
+        assert_eq!(config.grades.d, 20);
+    }
+
+    #[test]
+    fn contrast_floor_grade_from_toml() {
+        let config: TestConfig = toml::from_str(r#"contrast_floor_grade = "C""#).unwrap();
+        assert_eq!(config.contrast_floor_grade, IsoGrade::C);
+        assert_eq!(TestConfig::default().contrast_floor_grade, IsoGrade::F);
+        for bad in ["f", "E", "A+"] {
+            assert!(toml::from_str::<TestConfig>(&format!("contrast_floor_grade = {:?}", bad)).is_err());
+        }
     }
 }